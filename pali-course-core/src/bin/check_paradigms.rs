@@ -0,0 +1,5 @@
+//! Checks the written conjugation and declension tables against the generated ones: `cargo run --bin check-paradigms -- static/lessons/index.json`
+
+fn main() {
+    std::process::exit(pali_course_core::lint::run_paradigm_check(std::env::args().skip(1).collect()));
+}
@@ -0,0 +1,5 @@
+//! Checks lesson json files for authoring mistakes: `cargo run --bin lint-lessons -- static/lessons/index.json`
+
+fn main() {
+    std::process::exit(pali_course_core::lint::run(std::env::args().skip(1).collect()));
+}
@@ -0,0 +1,150 @@
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::sandhi::combine;
+use crate::table::{ParsedCell, ParsedTable};
+
+/**
+ * How close a typed answer has to be. Each one also allows what the ones before it allow.
+ */
+#[derive(PartialEq, Clone, Default, Deserialize, Debug)]
+pub enum CheckStrictness {
+    #[default]
+    Exact,
+    IgnoreCase,
+    IgnorePunctuation,
+}
+
+/**
+ * Cells whose answers are worked out when the table is, instead of being what's written in them
+ */
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+pub enum AnswerCheck {
+    Sandhi, // the last column is the first two combined, in any way the rules allow
+}
+
+impl AnswerCheck {
+
+    /// gives the cells it checks their answers; the written ones are still what's shown
+    pub fn apply(&self, parsed_table: &mut ParsedTable) {
+        match self {
+            AnswerCheck::Sandhi => for row in parsed_table.iter_mut() {
+                let [first, second, ParsedCell::Interactive(combined)] = row.as_mut_slice() else { continue };
+                if let Ok(answers) = combine(cell_text(first).as_str(), cell_text(second).as_str()) {
+                    combined.answers = answers;
+                }
+            },
+        }
+    }
+
+}
+
+fn cell_text(cell: &ParsedCell) -> String {
+    match cell {
+        ParsedCell::Label(label) => label.clone(),
+        ParsedCell::Interactive(text) => format!("{}{}{}", text.start, text.middle, text.end),
+    }
+}
+
+/// None when nothing was answered, otherwise whether the content matches any of the answers
+pub fn check_answer(content: &str, answers: &[String], strictness: &CheckStrictness) -> Option<bool> {
+    let content = normalize_answer(content, strictness);
+    if content.is_empty() {
+        return None;
+    }
+    Some(answers.iter().any(|answer: &String| normalize_answer(answer.as_str(), strictness) == content))
+}
+
+/**
+ * " bha\u{0304}vami\t" -> "bhāvami"; both sides of a comparison go through this.
+ * Composes diacritics (NFC), trims and collapses any unicode whitespace, then loosens by strictness.
+ */
+pub fn normalize_answer(text: &str, strictness: &CheckStrictness) -> String {
+    let composed: String = text.nfc().collect();
+    let loosened: String = match strictness {
+        CheckStrictness::Exact => composed,
+        CheckStrictness::IgnoreCase => composed.to_lowercase(),
+        CheckStrictness::IgnorePunctuation => composed.to_lowercase().chars()
+            .filter(|c: &char| c.is_alphanumeric() || c.is_whitespace() || is_combining_mark(*c))
+            .collect(),
+    };
+    loosened.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::{create_parsed_table, TriSplit};
+    use CheckStrictness::*;
+
+    fn answers(answers: &[&str]) -> Vec<String> {
+        answers.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn nothing_typed_isnt_checked() {
+        assert_eq!(check_answer("", &answers(&["kāya"]), &Exact), None);
+        assert_eq!(check_answer("  \t", &answers(&["kāya"]), &Exact), None);
+    }
+
+    #[test]
+    fn exact_match_is_correct() {
+        assert_eq!(check_answer("kāya", &answers(&["kāya"]), &Exact), Some(true));
+        assert_eq!(check_answer("kaya", &answers(&["kāya"]), &Exact), Some(false));
+    }
+
+    #[test]
+    fn any_alternative_is_correct() {
+        let answers = answers(&["says, speaks", "says", "speaks"]);
+        assert_eq!(check_answer("speaks", &answers, &Exact), Some(true));
+        assert_eq!(check_answer("talks", &answers, &Exact), Some(false));
+    }
+
+    #[test]
+    fn decomposed_diacritics_and_extra_spaces_are_accepted() {
+        assert_eq!(check_answer(" ka\u{0304}ya ", &answers(&["kāya"]), &Exact), Some(true));
+        assert_eq!(check_answer("goes   away", &answers(&["goes away"]), &Exact), Some(true));
+    }
+
+    #[test]
+    fn case_only_matters_when_exact() {
+        assert_eq!(check_answer("Kāya", &answers(&["kāya"]), &Exact), Some(false));
+        assert_eq!(check_answer("Kāya", &answers(&["kāya"]), &IgnoreCase), Some(true));
+        assert_eq!(check_answer("Kāya", &answers(&["kāya"]), &IgnorePunctuation), Some(true));
+    }
+
+    #[test]
+    fn punctuation_is_ignored_but_not_diacritics() {
+        assert_eq!(check_answer("I am.", &answers(&["I am"]), &IgnoreCase), Some(false));
+        assert_eq!(check_answer("I am.", &answers(&["I am"]), &IgnorePunctuation), Some(true));
+        assert_eq!(check_answer("kaya", &answers(&["kāya"]), &IgnorePunctuation), Some(false));
+        assert_eq!(normalize_answer("ka\u{0304}ya!", &IgnorePunctuation), "kāya");
+    }
+
+    #[test]
+    fn sandhi_cells_accept_every_combination() {
+        let mut parsed_table = create_parsed_table(&vec![
+            vec!["first".to_string(), "second".to_string(), "combined".to_string()],
+            vec!["su".to_string(), "āgataṃ".to_string(), "|svāgataṃ|".to_string()],
+            vec!["|saṃ|".to_string(), "yoga".to_string(), "saṃyoga".to_string()],
+        ]);
+        AnswerCheck::Sandhi.apply(&mut parsed_table);
+        let ParsedCell::Interactive(combined) = &parsed_table[1][2] else { panic!() };
+        assert_eq!(combined.middle, "svāgataṃ");
+        assert_eq!(check_answer("sāgataṃ", &combined.answers, &Exact), Some(true));
+        assert_eq!(check_answer("suāgataṃ", &combined.answers, &Exact), Some(false));
+        assert_eq!(parsed_table[2][0], ParsedCell::Interactive(TriSplit::new(String::new(), "saṃ".to_string(), String::new())));
+    }
+
+    #[test]
+    fn sandhi_cells_reject_dropping_a_before_i() {
+        let mut parsed_table = create_parsed_table(&vec![
+            vec!["upa".to_string(), "ikkhati".to_string(), "|upekkhati|".to_string()],
+        ]);
+        AnswerCheck::Sandhi.apply(&mut parsed_table);
+        let ParsedCell::Interactive(combined) = &parsed_table[0][2] else { panic!() };
+        assert_eq!(check_answer("upekkhati", &combined.answers, &Exact), Some(true));
+        assert_eq!(check_answer("upikkhati", &combined.answers, &Exact), Some(false));
+    }
+}
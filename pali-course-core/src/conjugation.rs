@@ -0,0 +1,165 @@
+use serde::Deserialize;
+
+use crate::model::TableLayout;
+use crate::table::DataTable;
+
+/**
+ * A regular verb's paradigm for one tense, from its bare stem ("bhav") and conjugation class.
+ * Irregular verbs (karoti's aorist akāsi, atthi) still have to be written out.
+ */
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+pub struct Conjugation {
+    pub stem: String, // without the class's vowel, "bhav-" or "bhav"
+    pub class: u8, // 1 to 7, as the textbook numbers them
+    pub tense: Tense,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Deserialize, Debug)]
+pub enum Tense {
+    Present,
+    Imperative,
+    Optative,
+    Future,
+    Aorist,
+}
+
+/// 3rd, 2nd and 1st person, each singular then plural
+type Endings = [&'static str; 6];
+
+const PRESENT: Endings = ["ti", "nti", "si", "tha", "mi", "ma"];
+const IMPERATIVE: Endings = ["tu", "ntu", "hi", "tha", "mi", "ma"];
+const OPTATIVE: Endings = ["eyya", "eyyuṃ", "eyyāsi", "eyyātha", "eyyaṃ", "eyyāma"];
+const FUTURE: Endings = ["issati", "issanti", "issasi", "issatha", "issāmi", "issāma"];
+const FIRST_AORIST: Endings = ["i", "iṃsu", "i", "ittha", "iṃ", "imha/imhā"];
+const SECOND_AORIST: Endings = ["esi", "uṃ", "esi", "ittha", "esiṃ", "imha"];
+
+pub const PERSONS: [&str; 3] = ["3rd", "2nd", "1st"];
+
+impl Conjugation {
+
+    /// the vowel the class puts between the stem and the endings
+    fn vowel(&self) -> Result<char, String> {
+        match self.class {
+            1..=3 => Ok('a'), // bhavati, rundhati, maññati
+            4 | 6 => Ok('o'), // suṇoti, tanoti
+            5 => Ok('ā'), // kiṇāti
+            7 => Ok('e'), // deseti
+            class => Err(format!("unknown conjugation class {}", class)),
+        }
+    }
+
+    fn stem(&self) -> Result<&str, String> {
+        let stem = self.stem.trim().trim_end_matches('-');
+        if stem.is_empty() {
+            return Err("the stem is empty".to_string());
+        }
+        Ok(stem)
+    }
+
+    /**
+     * The six forms with the ending |marked|. An a or ā vowel is marked with the ending since it changes with it
+     * ("bhav|āmi|"), e and o stay with the stem ("dese|ti|").
+     */
+    pub fn forms(&self) -> Result<[String; 6], String> {
+        let stem = self.stem()?;
+        let vowel = self.vowel()?;
+        let thematic = vowel == 'a' || vowel == 'ā';
+        let marked = |ending: &str| format!("{}|{}|", stem, ending);
+        let with_vowel = |ending: &str| {
+            if thematic {
+                let vowel = if ending.starts_with('m') || ending.starts_with('h') { 'ā' } else if ending.starts_with("nt") { 'a' } else { vowel };
+                format!("{}|{}{}|", stem, vowel, ending)
+            } else {
+                format!("{}{}|{}|", stem, vowel, ending)
+            }
+        };
+
+        let forms: Vec<String> = match self.tense {
+            Tense::Present => PRESENT.iter().map(|ending| with_vowel(ending)).collect(),
+            Tense::Imperative => IMPERATIVE.iter().enumerate().map(|(i, ending)| match (i, vowel) {
+                (2, 'a') => marked("a/āhi"), // bhava or bhavāhi
+                _ => with_vowel(ending),
+            }).collect(),
+            // e-stems keep their e: deseyya, desessati
+            Tense::Optative if vowel == 'e' => OPTATIVE.iter().map(|ending| format!("{}e|{}|", stem, &ending[1..])).collect(),
+            Tense::Optative => OPTATIVE.iter().map(|ending| marked(ending)).collect(),
+            Tense::Future if vowel == 'e' => FUTURE.iter().map(|ending| format!("{}e|{}|", stem, &ending[1..])).collect(),
+            Tense::Future => FUTURE.iter().map(|ending| marked(ending)).collect(),
+            Tense::Aorist if vowel == 'e' => SECOND_AORIST.iter().map(|ending| marked(ending)).collect(),
+            Tense::Aorist => FIRST_AORIST.iter().map(|ending| marked(ending)).collect(),
+        };
+        Ok(forms.try_into().expect("six endings"))
+    }
+
+    /// laid out like the textbook's paradigms: a row for each person, singular and plural columns
+    pub fn table_layout(&self) -> Result<TableLayout, String> {
+        let forms = self.forms()?;
+        let mut table: DataTable = vec![vec!["person".to_string(), "singular".to_string(), "plural".to_string()]];
+        for (person, numbers) in PERSONS.iter().zip(forms.chunks(2)) {
+            table.push(vec![person.to_string(), numbers[0].clone(), numbers[1].clone()]);
+        }
+        Ok(TableLayout {
+            pali_cols: Some(vec![1, 2]),
+            ..TableLayout::new(table)
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forms(stem: &str, class: u8, tense: Tense) -> Vec<String> {
+        Conjugation { stem: stem.to_string(), class, tense }.forms().unwrap().to_vec()
+    }
+
+    #[test]
+    fn first_class_present_is_the_textbooks_bhavati() {
+        assert_eq!(forms("bhav-", 1, Tense::Present), vec!["bhav|ati|", "bhav|anti|", "bhav|asi|", "bhav|atha|", "bhav|āmi|", "bhav|āma|"]);
+    }
+
+    #[test]
+    fn seventh_class_keeps_its_e_with_the_stem() {
+        assert_eq!(forms("des", 7, Tense::Present), vec!["dese|ti|", "dese|nti|", "dese|si|", "dese|tha|", "dese|mi|", "dese|ma|"]);
+        assert_eq!(forms("des", 7, Tense::Optative)[0], "dese|yya|");
+        assert_eq!(forms("des", 7, Tense::Future)[4], "dese|ssāmi|");
+    }
+
+    #[test]
+    fn long_a_is_shortened_before_nt() {
+        assert_eq!(forms("kiṇ", 5, Tense::Present)[..2], ["kiṇ|āti|", "kiṇ|anti|"]);
+    }
+
+    #[test]
+    fn imperative_second_singular_has_both_forms() {
+        assert_eq!(forms("bhav", 1, Tense::Imperative), vec!["bhav|atu|", "bhav|antu|", "bhav|a/āhi|", "bhav|atha|", "bhav|āmi|", "bhav|āma|"]);
+        assert_eq!(forms("des", 7, Tense::Imperative)[2], "dese|hi|");
+    }
+
+    #[test]
+    fn optative_and_future() {
+        assert_eq!(forms("bhav", 1, Tense::Optative), vec!["bhav|eyya|", "bhav|eyyuṃ|", "bhav|eyyāsi|", "bhav|eyyātha|", "bhav|eyyaṃ|", "bhav|eyyāma|"]);
+        assert_eq!(forms("bhav", 1, Tense::Future)[1], "bhav|issanti|");
+    }
+
+    #[test]
+    fn aorist_form_follows_the_class() {
+        assert_eq!(forms("upasaṃkam", 1, Tense::Aorist), vec!["upasaṃkam|i|", "upasaṃkam|iṃsu|", "upasaṃkam|i|", "upasaṃkam|ittha|", "upasaṃkam|iṃ|", "upasaṃkam|imha/imhā|"]);
+        assert_eq!(forms("des", 7, Tense::Aorist), vec!["des|esi|", "des|uṃ|", "des|esi|", "des|ittha|", "des|esiṃ|", "des|imha|"]);
+    }
+
+    #[test]
+    fn table_has_a_row_for_each_person() {
+        let layout = Conjugation { stem: "bhav".to_string(), class: 1, tense: Tense::Present }.table_layout().unwrap();
+        assert_eq!(layout.table[0], vec!["person", "singular", "plural"]);
+        assert_eq!(layout.table[3], vec!["1st", "bhav|āmi|", "bhav|āma|"]);
+        assert_eq!(layout.pali_cols, Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn unknown_classes_and_empty_stems_are_errors() {
+        assert_eq!(Conjugation { stem: "bhav".to_string(), class: 9, tense: Tense::Present }.forms(), Err("unknown conjugation class 9".to_string()));
+        assert!(Conjugation { stem: " -".to_string(), class: 1, tense: Tense::Present }.forms().is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::check::{normalize_answer, CheckStrictness};
+use crate::conjugation::{Conjugation, Tense, PERSONS};
+use crate::declension::{Case, Declension, Gender, StemClass, CASES};
+use crate::model::{Exercise, Paradigm};
+use crate::table::{create_parsed_table, split_bars, Location, ParsedCell, ParsedTable};
+
+/// categories whose tables are checked when they're shaped like a paradigm
+const VERB_CATEGORIES: [&str; 3] = ["conjugation", "tam", "aorist"];
+const NOUN_CATEGORIES: [&str; 1] = ["declension"];
+
+const TENSES: [Tense; 5] = [Tense::Present, Tense::Imperative, Tense::Optative, Tense::Future, Tense::Aorist];
+const STEM_CLASSES: [StemClass; 6] = [StemClass::A, StemClass::Aa, StemClass::I, StemClass::Ii, StemClass::U, StemClass::Consonant];
+const GENDERS: [Gender; 3] = [Gender::Masculine, Gender::Feminine, Gender::Neuter];
+
+/// a written cell with a form the paradigm doesn't make
+#[derive(PartialEq, Clone, Debug)]
+pub struct Mismatch {
+    pub location: Location,
+    pub written: String,
+    pub expected: String, // the paradigm's cell
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct ParadigmCheck {
+    pub paradigm: Paradigm, // the exercise's own, or the one that fits most of the table
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// a cell that's compared, with where its form is in the paradigm: (person or case index, singular 0 or plural 1)
+type Slot = (Location, usize, usize);
+
+/**
+ * Compares a written paradigm table with what the morphology engine makes for it. Exercises with their own
+ * paradigm are compared with it; conjugation and declension tables are compared with the verb or noun that fits the
+ * most cells, when that's more than half of them.
+ * None when the table isn't one of those or nothing fits, so irregular verbs and pronouns aren't reported.
+ */
+pub fn cross_check(exercise: &Exercise) -> Option<ParadigmCheck> {
+    let table_layout = exercise.table_layout.as_ref()?;
+    let parsed_table = create_parsed_table(&table_layout.table);
+    let in_categories = |ids: &[&str]| exercise.categories.iter().flatten().any(|id| ids.contains(&id.as_str()));
+
+    let (paradigm, slots) = match &exercise.paradigm {
+        Some(paradigm @ Paradigm::Conjugation(_)) => (paradigm.clone(), person_slots(&parsed_table)?),
+        Some(paradigm @ Paradigm::Declension(_)) => (paradigm.clone(), case_slots(&parsed_table)?),
+        None if in_categories(&VERB_CATEGORIES) => {
+            let slots = person_slots(&parsed_table)?;
+            (best_fit(&parsed_table, &slots, conjugations)?, slots)
+        },
+        None if in_categories(&NOUN_CATEGORIES) => {
+            let slots = case_slots(&parsed_table)?;
+            (best_fit(&parsed_table, &slots, declensions)?, slots)
+        },
+        Some(Paradigm::Syllables(_) | Paradigm::Sandhi(_)) | None => return None,
+    };
+    let expected = paradigm_cells(&paradigm)?;
+    let mismatches = slots.iter()
+        .filter(|slot| !fits(&parsed_table, slot, &expected))
+        .map(|(location, row, number)| Mismatch {
+            location: *location,
+            written: table_layout.table[location.0][location.1].clone(),
+            expected: expected[*row][*number].clone(),
+        })
+        .collect();
+    Some(ParadigmCheck { paradigm, mismatches })
+}
+
+/// the paradigm's cells by person or case, all eight cases for a declension
+fn paradigm_cells(paradigm: &Paradigm) -> Option<Vec<[String; 2]>> {
+    match paradigm {
+        Paradigm::Conjugation(conjugation) => {
+            let forms = conjugation.forms().ok()?;
+            Some(forms.chunks(2).map(|pair| [pair[0].clone(), pair[1].clone()]).collect())
+        },
+        Paradigm::Declension(declension) => {
+            let all_cases = Declension { cases: None, ..declension.clone() };
+            Some(all_cases.forms().ok()?.into_iter().map(|(_, forms)| forms).collect())
+        },
+        Paradigm::Syllables(_) | Paradigm::Sandhi(_) => None,
+    }
+}
+
+/// the interactive cells of a table with "singular" and "plural" columns and a row for each of the labels
+fn slots(parsed_table: &ParsedTable, row_index: impl Fn(&str) -> Option<usize>) -> Option<Vec<Slot>> {
+    let header = parsed_table.first()?;
+    let number = |col: usize| match header.get(col) {
+        Some(ParsedCell::Label(label)) if label.contains("sing") => Some(0),
+        Some(ParsedCell::Label(label)) if label.contains("plur") => Some(1),
+        _ => None,
+    };
+    let mut slots = vec![];
+    for (r, row) in parsed_table.iter().enumerate().skip(1) {
+        let Some(ParsedCell::Label(label)) = row.first() else { return None };
+        let index = row_index(label.trim())?;
+        for (c, cell) in row.iter().enumerate().skip(1) {
+            if let (true, Some(number)) = (cell.is_interactive(), number(c)) {
+                slots.push(((r, c), index, number));
+            }
+        }
+    }
+    (!slots.is_empty()).then_some(slots)
+}
+
+fn person_slots(parsed_table: &ParsedTable) -> Option<Vec<Slot>> {
+    slots(parsed_table, |label| PERSONS.iter().position(|person| *person == label))
+}
+
+fn case_slots(parsed_table: &ParsedTable) -> Option<Vec<Slot>> {
+    slots(parsed_table, |label| CASES.iter().position(|case: &Case| case.label() == label))
+}
+
+/// every form the cell accepts, "bhav|a/āhi|" -> ["bhava", "bhavāhi"]
+fn full_forms(cell: &ParsedCell) -> Vec<String> {
+    let forms = match cell {
+        ParsedCell::Label(label) => vec![label.clone()],
+        ParsedCell::Interactive(text) => {
+            let alternatives = if text.answers.len() > 1 { &text.answers[1..] } else { &text.answers[..] };
+            alternatives.iter().map(|answer| format!("{}{}{}", text.start, answer, text.end)).collect()
+        },
+    };
+    forms.iter().map(|form| normalize_answer(form, &CheckStrictness::Exact)).collect()
+}
+
+/// the written forms are all ones the paradigm makes; the textbook often gives fewer than there are
+fn fits(parsed_table: &ParsedTable, (location, row, number): &Slot, expected: &[[String; 2]]) -> bool {
+    let Some(expected) = expected.get(*row) else { return false };
+    let expected = full_forms(&split_bars(expected[*number].clone()));
+    full_forms(&parsed_table[location.0][location.1]).iter().all(|form| expected.contains(form))
+}
+
+/// the candidate that fits the most cells, if that's more than half
+fn best_fit(parsed_table: &ParsedTable, slots: &[Slot], candidates: fn(&str) -> Vec<Paradigm>) -> Option<Paradigm> {
+    let (first_location, _, _) = slots.first()?;
+    let first_form = full_forms(&parsed_table[first_location.0][first_location.1]).into_iter().next()?;
+    let stems = first_form.char_indices().skip(1).map(|(i, _)| &first_form[..i]).chain([first_form.as_str()]);
+    let mut best: Option<(usize, Paradigm)> = None;
+    for paradigm in stems.flat_map(candidates) {
+        let Some(expected) = paradigm_cells(&paradigm) else { continue };
+        let score = slots.iter().filter(|slot| fits(parsed_table, slot, &expected)).count();
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, paradigm));
+        }
+    }
+    best.filter(|(score, _)| score * 2 > slots.len()).map(|(_, paradigm)| paradigm)
+}
+
+fn conjugations(stem: &str) -> Vec<Paradigm> {
+    (1..=7).flat_map(|class| TENSES.iter().map(move |tense| Paradigm::Conjugation(Conjugation { stem: stem.to_string(), class, tense: *tense })))
+        .collect()
+}
+
+fn declensions(stem: &str) -> Vec<Paradigm> {
+    STEM_CLASSES.iter()
+        .flat_map(|stem_class| GENDERS.iter().map(move |gender| Paradigm::Declension(Declension { stem: stem.to_string(), stem_class: *stem_class, gender: *gender, cases: None })))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(categories: &[&str], table: &[&[&str]]) -> Exercise {
+        let mut exercise: Exercise = serde_json::from_str(r#"{ "title": "Table" }"#).unwrap();
+        exercise.categories = Some(categories.iter().map(|c| c.to_string()).collect());
+        exercise.table_layout = Some(crate::model::TableLayout::new(table.iter().map(|row| row.iter().map(|c| c.to_string()).collect()).collect()));
+        exercise
+    }
+
+    #[test]
+    fn a_typo_in_a_conjugation_is_reported() {
+        let check = cross_check(&exercise(&["conjugation"], &[
+            &["person", "singular", "plural"],
+            &["3rd", "bhav|ati|", "bhav|anti|"],
+            &["2nd", "bhav|asi|", "bhav|atha|"],
+            &["1st", "bhav|āmi|", "bhav|ama|"],
+        ])).unwrap();
+        assert_eq!(check.paradigm, Paradigm::Conjugation(Conjugation { stem: "bhav".to_string(), class: 1, tense: Tense::Present }));
+        assert_eq!(check.mismatches, vec![Mismatch { location: (3, 2), written: "bhav|ama|".to_string(), expected: "bhav|āma|".to_string() }]);
+    }
+
+    #[test]
+    fn markers_can_be_anywhere_and_fewer_alternatives_are_fine() {
+        let check = cross_check(&exercise(&["tam"], &[
+            &["person", "singular", "plural"],
+            &["3rd", "|deseti|", "dese|nti|"],
+            &["2nd", "de|sesi|", "dese|tha|"],
+        ])).unwrap();
+        assert!(check.mismatches.is_empty());
+        let check = cross_check(&exercise(&["aorist"], &[
+            &["person", "singular", "plural"],
+            &["3rd", "upasaṃkam|i|", "upasaṃkam|iṃsu|"],
+            &["1st", "upasaṃkam|iṃ|", "upasaṃkam|imha|"],
+        ])).unwrap();
+        assert!(check.mismatches.is_empty());
+    }
+
+    #[test]
+    fn declensions_are_found_by_their_case_rows() {
+        let check = cross_check(&exercise(&["declension"], &[
+            &["case", "singular", "plural"],
+            &["nom.", "kath|ā|", "kath|ā/āyo|"],
+            &["acc.", "kath|aṃ|", "kath|āyo|"],
+            &["instr.", "kath|āya|", "kath|āhī|"],
+        ])).unwrap();
+        assert_eq!(check.mismatches.len(), 1);
+        assert_eq!(check.mismatches[0].expected, "kath|āhi|");
+    }
+
+    #[test]
+    fn irregular_and_uncategorized_tables_are_left_alone() {
+        let atthi: &[&[&str]] = &[&["person", "singular", "plural"], &["3rd", "|atthi|", "|santi|"], &["2nd", "|asi|", "|attha|"], &["1st", "|asmi/amhi|", "|amha/amhā|"]];
+        assert_eq!(cross_check(&exercise(&["conjugation"], atthi)), None);
+        let pronoun: &[&[&str]] = &[&["case", "singular", "plural"], &["nom.", "I |ahaṃ|", "we |mayaṃ|"]];
+        assert_eq!(cross_check(&exercise(&[], pronoun)), None);
+    }
+}
@@ -0,0 +1,303 @@
+use serde::Deserialize;
+
+use crate::model::TableLayout;
+use crate::table::DataTable;
+
+/**
+ * A noun's forms in each case, from its stem without the stem vowel ("puris-" for puriso), stem class and gender.
+ * The irregular nouns in IRREGULARS have some or all of their forms written out instead.
+ */
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+pub struct Declension {
+    pub stem: String,
+    pub stem_class: StemClass,
+    pub gender: Gender,
+    pub cases: Option<Vec<Case>>, // the rows, in order. All eight when left out
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Deserialize, Debug)]
+pub enum StemClass {
+    A, // puriso, rūpaṃ
+    Aa, // kathā
+    I, // aggi, akkhi, ratti
+    Ii, // daṇḍī, nadī
+    U, // bhikkhu, cakkhu, dhenu
+    Consonant, // -ant: gacchaṃ, and bhagavā with -vant or -mant. Other consonant stems are in IRREGULARS
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Deserialize, Debug)]
+pub enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Deserialize, Debug)]
+pub enum Case {
+    Nominative,
+    Accusative,
+    Instrumental,
+    Dative,
+    Ablative,
+    Genitive,
+    Locative,
+    Vocative,
+}
+
+pub const CASES: [Case; 8] = [Case::Nominative, Case::Accusative, Case::Instrumental, Case::Dative, Case::Ablative, Case::Genitive, Case::Locative, Case::Vocative];
+
+impl Case {
+    /// as the textbook's tables label it
+    pub fn label(&self) -> &'static str {
+        match self {
+            Case::Nominative => "nom.",
+            Case::Accusative => "acc.",
+            Case::Instrumental => "instr.",
+            Case::Dative => "dat.",
+            Case::Ablative => "abl.",
+            Case::Genitive => "gen.",
+            Case::Locative => "loc.",
+            Case::Vocative => "voc.",
+        }
+    }
+}
+
+/// [singular, plural] for each case, in the order of CASES
+type Endings = [[&'static str; 2]; 8];
+
+const A_MASCULINE: Endings = [
+    ["o", "ā"], ["aṃ", "e"], ["ena", "ehi"], ["assa/āya", "ānaṃ"], ["ā/asmā/amhā", "ehi"], ["assa", "ānaṃ"], ["e/asmiṃ/amhi", "esu"], ["a", "ā"],
+];
+const AA_FEMININE: Endings = [
+    ["ā", "ā/āyo"], ["aṃ", "āyo"], ["āya", "āhi"], ["āya", "ānaṃ"], ["āya", "āhi"], ["āya", "ānaṃ"], ["āya/āyaṃ", "āsu"], ["e", "ā/āyo"],
+];
+const I_MASCULINE: Endings = [
+    ["i", "ī/ayo"], ["iṃ", "ī/ayo"], ["inā", "īhi"], ["ino/issa", "īnaṃ"], ["inā/ismā/imhā", "īhi"], ["ino/issa", "īnaṃ"], ["ismiṃ/imhi", "īsu"], ["i", "ī/ayo"],
+];
+const I_FEMININE: Endings = [
+    ["i", "ī/iyo"], ["iṃ", "ī/iyo"], ["iyā", "īhi"], ["iyā", "īnaṃ"], ["iyā", "īhi"], ["iyā", "īnaṃ"], ["iyā/iyaṃ", "īsu"], ["i", "ī/iyo"],
+];
+const II_MASCULINE: Endings = [
+    ["ī", "ī/ino"], ["iṃ/inaṃ", "ī/ino"], ["inā", "īhi"], ["ino/issa", "īnaṃ"], ["inā/ismā/imhā", "īhi"], ["ino/issa", "īnaṃ"], ["ismiṃ/imhi/ini", "īsu"], ["i", "ī/ino"],
+];
+const II_FEMININE: Endings = [
+    ["ī", "ī/iyo"], ["iṃ", "ī/iyo"], ["iyā", "īhi"], ["iyā", "īnaṃ"], ["iyā", "īhi"], ["iyā", "īnaṃ"], ["iyā/iyaṃ", "īsu"], ["i", "ī/iyo"],
+];
+const U_MASCULINE: Endings = [
+    ["u", "ū/avo"], ["uṃ", "ū/avo"], ["unā", "ūhi"], ["uno/ussa", "ūnaṃ"], ["unā/usmā/umhā", "ūhi"], ["uno/ussa", "ūnaṃ"], ["usmiṃ/umhi", "ūsu"], ["u", "ū/ave/avo"],
+];
+const U_FEMININE: Endings = [
+    ["u", "ū/uyo"], ["uṃ", "ū/uyo"], ["uyā", "ūhi"], ["uyā", "ūnaṃ"], ["uyā", "ūhi"], ["uyā", "ūnaṃ"], ["uyā/uyaṃ", "ūsu"], ["u", "ū/uyo"],
+];
+const ANT_MASCULINE: Endings = [
+    ["aṃ/anto", "antā"], ["antaṃ", "ante"], ["atā", "antehi"], ["ato/antassa", "ataṃ/antānaṃ"], ["atā/antasmā", "antehi"], ["ato/antassa", "ataṃ/antānaṃ"], ["ati/antasmiṃ", "antesu"], ["aṃ", "anto"],
+];
+
+/// neuters only differ from the masculine in the nominative, accusative and vocative
+const fn neuter(masculine: Endings, nominative: [&'static str; 2], accusative: [&'static str; 2], vocative: [&'static str; 2]) -> Endings {
+    let mut endings = masculine;
+    endings[0] = nominative;
+    endings[1] = accusative;
+    endings[7] = vocative;
+    endings
+}
+
+/**
+ * Nouns that aren't declined like their stem class, by the stem they're given with.
+ * Forms are written with their own |markers| and replace the regular ones; nouns with every form here don't need a regular declension.
+ */
+struct Irregular {
+    stem: &'static str,
+    gender: Gender,
+    forms: &'static [(Case, [&'static str; 2])],
+}
+
+const IRREGULARS: &[Irregular] = &[
+    Irregular { stem: "rājan", gender: Gender::Masculine, forms: &[
+        (Case::Nominative, ["rāj|ā|", "rāj|āno|"]),
+        (Case::Accusative, ["rāj|ānaṃ|", "rāj|āno|"]),
+        (Case::Instrumental, ["|raññā|", "rāj|ūhi|"]),
+        (Case::Dative, ["|rañño/rājino|", "|raññaṃ/rājūnaṃ|"]),
+        (Case::Ablative, ["|raññā|", "rāj|ūhi|"]),
+        (Case::Genitive, ["|rañño/rājino|", "|raññaṃ/rājūnaṃ|"]),
+        (Case::Locative, ["|raññe/rājini|", "rāj|ūsu|"]),
+        (Case::Vocative, ["rāj|a/ā|", "rāj|āno|"]),
+    ]},
+    Irregular { stem: "attan", gender: Gender::Masculine, forms: &[
+        (Case::Nominative, ["att|ā|", "att|āno|"]),
+        (Case::Accusative, ["att|ānaṃ/anaṃ|", "att|āno|"]),
+        (Case::Instrumental, ["att|anā|", "att|anehi|"]),
+        (Case::Dative, ["att|ano|", "att|ānaṃ|"]),
+        (Case::Ablative, ["att|anā|", "att|anehi|"]),
+        (Case::Genitive, ["att|ano|", "att|ānaṃ|"]),
+        (Case::Locative, ["att|ani|", "att|anesu|"]),
+        (Case::Vocative, ["att|a/ā|", "att|āno|"]),
+    ]},
+    Irregular { stem: "pitar", gender: Gender::Masculine, forms: &[
+        (Case::Nominative, ["pit|ā|", "pit|aro|"]),
+        (Case::Accusative, ["pit|araṃ|", "pit|aro/are|"]),
+        (Case::Instrumental, ["pit|arā/unā|", "pit|ūhi/arehi|"]),
+        (Case::Dative, ["pit|u/uno/ussa|", "pit|ūnaṃ/ānaṃ/arānaṃ|"]),
+        (Case::Ablative, ["pit|arā|", "pit|ūhi/arehi|"]),
+        (Case::Genitive, ["pit|u/uno/ussa|", "pit|ūnaṃ/ānaṃ/arānaṃ|"]),
+        (Case::Locative, ["pit|ari|", "pit|ūsu/aresu|"]),
+        (Case::Vocative, ["pit|a/ā|", "pit|aro|"]),
+    ]},
+    Irregular { stem: "mātar", gender: Gender::Feminine, forms: &[
+        (Case::Nominative, ["māt|ā|", "māt|aro|"]),
+        (Case::Accusative, ["māt|araṃ|", "māt|aro/are|"]),
+        (Case::Instrumental, ["māt|arā/uyā|", "māt|ūhi/arehi|"]),
+        (Case::Dative, ["māt|u/uyā|", "māt|ūnaṃ/ānaṃ/arānaṃ|"]),
+        (Case::Ablative, ["māt|arā/uyā|", "māt|ūhi/arehi|"]),
+        (Case::Genitive, ["māt|u/uyā|", "māt|ūnaṃ/ānaṃ/arānaṃ|"]),
+        (Case::Locative, ["māt|ari/uyā/uyaṃ|", "māt|ūsu/aresu|"]),
+        (Case::Vocative, ["māt|a/ā|", "māt|aro|"]),
+    ]},
+    // mano and the other -as neuters: an a-stem except for a few singulars
+    Irregular { stem: "man", gender: Gender::Neuter, forms: &[
+        (Case::Nominative, ["man|o/aṃ|", "man|āni|"]),
+        (Case::Instrumental, ["man|asā|", "man|ehi|"]),
+        (Case::Dative, ["man|aso|", "man|ānaṃ|"]),
+        (Case::Ablative, ["man|asā|", "man|ehi|"]),
+        (Case::Genitive, ["man|aso|", "man|ānaṃ|"]),
+        (Case::Locative, ["man|asi|", "man|esu|"]),
+    ]},
+];
+
+impl Declension {
+
+    fn stem(&self) -> Result<&str, String> {
+        let stem = self.stem.trim().trim_end_matches('-');
+        if stem.is_empty() {
+            return Err("the stem is empty".to_string());
+        }
+        Ok(stem)
+    }
+
+    fn endings(&self, stem: &str) -> Result<Endings, String> {
+        use Gender::*;
+        use StemClass::*;
+        Ok(match (self.stem_class, self.gender) {
+            (A, Masculine) => A_MASCULINE,
+            (A, Neuter) => neuter(A_MASCULINE, ["aṃ", "āni"], ["aṃ", "āni"], ["a", "āni"]),
+            (Aa, Feminine) => AA_FEMININE,
+            (I, Masculine) => I_MASCULINE,
+            (I, Neuter) => neuter(I_MASCULINE, ["i", "ī/īni"], ["i/iṃ", "ī/īni"], ["i", "ī/īni"]),
+            (I, Feminine) => I_FEMININE,
+            (Ii, Masculine) => II_MASCULINE,
+            (Ii, Feminine) => II_FEMININE,
+            (U, Masculine) => U_MASCULINE,
+            (U, Neuter) => neuter(U_MASCULINE, ["u/uṃ", "ū/ūni"], ["u/uṃ", "ū/ūni"], ["u", "ū/ūni"]),
+            (U, Feminine) => U_FEMININE,
+            // possessives like bhagavant have bhagavā, the participles gacchaṃ
+            (Consonant, Masculine) if stem.ends_with('v') || stem.ends_with('m') => {
+                let mut endings = ANT_MASCULINE;
+                endings[0] = ["ā", "anto/antā"];
+                endings[7] = ["a/ā", "anto"];
+                endings
+            },
+            (Consonant, Masculine) => ANT_MASCULINE,
+            (Consonant, Neuter) => neuter(ANT_MASCULINE, ["aṃ", "antāni"], ["aṃ", "antāni"], ["aṃ", "antāni"]),
+            (stem_class, gender) => return Err(format!("{:?} stems aren't declined as {:?}", stem_class, gender)),
+        })
+    }
+
+    /// [singular, plural] in each of the cases with the ending |marked|
+    pub fn forms(&self) -> Result<Vec<(Case, [String; 2])>, String> {
+        let stem = self.stem()?;
+        let irregular = IRREGULARS.iter().find(|irregular| irregular.stem == stem && irregular.gender == self.gender);
+        let regular = self.endings(stem);
+        self.cases.clone().unwrap_or(CASES.to_vec()).into_iter()
+            .map(|case| {
+                let written = irregular.and_then(|irregular| irregular.forms.iter().find(|(c, _)| *c == case));
+                let forms = match (written, &regular) {
+                    (Some((_, forms)), _) => forms.map(|form| form.to_string()),
+                    (None, Ok(endings)) => endings[case as usize].map(|ending| format!("{}|{}|", stem, ending)),
+                    (None, Err(message)) => return Err(message.clone()),
+                };
+                Ok((case, forms))
+            })
+            .collect()
+    }
+
+    /// laid out like the textbook's: a row for each case, singular and plural columns
+    pub fn table_layout(&self) -> Result<TableLayout, String> {
+        let mut table: DataTable = vec![vec!["case".to_string(), "singular".to_string(), "plural".to_string()]];
+        for (case, [singular, plural]) in self.forms()? {
+            table.push(vec![case.label().to_string(), singular, plural]);
+        }
+        Ok(TableLayout {
+            pali_cols: Some(vec![1, 2]),
+            ..TableLayout::new(table)
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declension(stem: &str, stem_class: StemClass, gender: Gender) -> Declension {
+        Declension { stem: stem.to_string(), stem_class, gender, cases: None }
+    }
+
+    fn form(declension: &Declension, case: Case) -> [String; 2] {
+        declension.forms().unwrap().into_iter().find(|(c, _)| *c == case).unwrap().1
+    }
+
+    #[test]
+    fn a_stem_masculine_in_every_case() {
+        let forms = declension("puris-", StemClass::A, Gender::Masculine).forms().unwrap();
+        assert_eq!(forms.len(), 8);
+        assert_eq!(forms[0], (Case::Nominative, ["puris|o|".to_string(), "puris|ā|".to_string()]));
+        assert_eq!(forms[6].1[1], "puris|esu|");
+    }
+
+    #[test]
+    fn neuters_differ_in_the_direct_cases() {
+        let rupa = declension("rūp", StemClass::A, Gender::Neuter);
+        assert_eq!(form(&rupa, Case::Accusative), ["rūp|aṃ|", "rūp|āni|"]);
+        assert_eq!(form(&rupa, Case::Instrumental), ["rūp|ena|", "rūp|ehi|"]);
+    }
+
+    #[test]
+    fn chosen_cases_are_the_textbooks_kathaa_table() {
+        let katha = Declension { cases: Some(vec![Case::Nominative, Case::Accusative, Case::Instrumental]), ..declension("kath", StemClass::Aa, Gender::Feminine) };
+        assert_eq!(katha.table_layout().unwrap().table, vec![
+            vec!["case", "singular", "plural"],
+            vec!["nom.", "kath|ā|", "kath|ā/āyo|"],
+            vec!["acc.", "kath|aṃ|", "kath|āyo|"],
+            vec!["instr.", "kath|āya|", "kath|āhi|"],
+        ]);
+    }
+
+    #[test]
+    fn i_and_u_stems() {
+        assert_eq!(form(&declension("agg", StemClass::I, Gender::Masculine), Case::Genitive), ["agg|ino/issa|", "agg|īnaṃ|"]);
+        assert_eq!(form(&declension("nad", StemClass::Ii, Gender::Feminine), Case::Locative), ["nad|iyā/iyaṃ|", "nad|īsu|"]);
+        assert_eq!(form(&declension("bhikkh", StemClass::U, Gender::Masculine), Case::Nominative), ["bhikkh|u|", "bhikkh|ū/avo|"]);
+    }
+
+    #[test]
+    fn consonant_stems_by_their_nominative() {
+        assert_eq!(form(&declension("gacch", StemClass::Consonant, Gender::Masculine), Case::Nominative), ["gacch|aṃ/anto|", "gacch|antā|"]);
+        assert_eq!(form(&declension("bhagav", StemClass::Consonant, Gender::Masculine), Case::Nominative), ["bhagav|ā|", "bhagav|anto/antā|"]);
+    }
+
+    #[test]
+    fn irregulars_replace_the_regular_forms() {
+        let raja = declension("rājan", StemClass::Consonant, Gender::Masculine);
+        assert_eq!(form(&raja, Case::Instrumental), ["|raññā|", "rāj|ūhi|"]);
+        let mano = declension("man", StemClass::A, Gender::Neuter);
+        assert_eq!(form(&mano, Case::Genitive), ["man|aso|", "man|ānaṃ|"]);
+        assert_eq!(form(&mano, Case::Accusative), ["man|aṃ|", "man|āni|"]); // regular
+        let mata = declension("mātar", StemClass::A, Gender::Feminine); // every form is written, so the class doesn't matter
+        assert_eq!(form(&mata, Case::Nominative), ["māt|ā|", "māt|aro|"]);
+    }
+
+    #[test]
+    fn unsupported_genders_are_errors() {
+        assert_eq!(declension("kath", StemClass::Aa, Gender::Masculine).forms(), Err("Aa stems aren't declined as Masculine".to_string()));
+    }
+}
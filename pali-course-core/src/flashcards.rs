@@ -0,0 +1,84 @@
+use crate::table::{Location, ParsedTable};
+
+/**
+ * The rows of a table gone through one at a time, remembering which ones weren't known
+ */
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct Deck {
+    pub rows: Vec<usize>, // in the order they're shown
+    pub position: usize,
+    pub missed: Vec<usize>,
+}
+
+impl Deck {
+
+    pub fn new(rows: Vec<usize>) -> Self {
+        Self { rows, ..Self::default() }
+    }
+
+    pub fn current(&self) -> Option<usize> {
+        self.rows.get(self.position).copied()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.rows.len()
+    }
+
+    pub fn known(&self) -> usize {
+        self.position.min(self.rows.len()) - self.missed.len()
+    }
+
+    /// moves on to the next row
+    pub fn answer(&mut self, knew: bool) {
+        let Some(row) = self.current() else { return };
+        if !knew {
+            self.missed.push(row);
+        }
+        self.position += 1;
+    }
+
+    /// a deck of only the rows that weren't known
+    pub fn missed_deck(&self) -> Deck {
+        Deck::new(self.missed.clone())
+    }
+
+}
+
+/// the rows, in the order of the location table, with a key column to show and something else to answer
+pub fn flashcard_rows(parsed_table: &ParsedTable, location_table: &[Vec<Location>], key_col: usize) -> Vec<usize> {
+    location_table.iter()
+        .filter_map(|row_locations| row_locations.first().map(|location| location.0))
+        .filter(|row| parsed_table[*row].len() > key_col)
+        .filter(|row| parsed_table[*row].iter().enumerate().any(|(col, cell)| col != key_col && cell.is_interactive()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::{create_location_table, create_parsed_table, table};
+
+    #[test]
+    fn rows_follow_the_location_table_and_skip_ones_with_nothing_to_answer() {
+        let table = table(&[&["noun", "meaning"], &["|kāya|", "|body|"], &["|kāla|", "time"], &["rūpa", "|form|"]]);
+        let parsed = create_parsed_table(&table);
+        let mut locations = create_location_table(&table);
+        assert_eq!(flashcard_rows(&parsed, &locations, 0), vec![1, 3]);
+        locations.swap(1, 3);
+        assert_eq!(flashcard_rows(&parsed, &locations, 1), vec![2, 1]);
+        assert_eq!(flashcard_rows(&parsed, &locations, 2), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn missed_rows_make_a_new_deck() {
+        let mut deck = Deck::new(vec![1, 2, 3]);
+        deck.answer(true);
+        deck.answer(false);
+        assert_eq!((deck.current(), deck.known()), (Some(3), 1));
+        deck.answer(false);
+        assert!(deck.is_finished());
+        deck.answer(true);
+        assert_eq!(deck.known(), 1);
+        assert_eq!(deck.missed_deck(), Deck::new(vec![2, 3]));
+    }
+}
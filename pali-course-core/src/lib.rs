@@ -0,0 +1,17 @@
+//! The lesson data and what's done with it, without anything for the browser.
+
+pub mod model;
+pub mod table;
+pub mod options;
+pub mod check;
+pub mod review;
+pub mod quiz;
+pub mod matching;
+pub mod flashcards;
+pub mod lint;
+pub mod conjugation;
+pub mod declension;
+pub mod crosscheck;
+pub mod prosody;
+pub mod sandhi;
+pub mod seed;
@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use crate::crosscheck::cross_check;
+use crate::matching::match_columns;
+use crate::model::{CourseIndex, ExerciseMode, Lesson, TableLayout};
+use crate::options::{DropDownOptionsStyle, OptionsStyleType, create_options_style, predict_options_style_type};
+use crate::table::{create_location_table, create_parsed_table};
+
+/**
+ * Checks lesson json for the mistakes the site would silently show wrong.
+ * Takes the fetched index.json (which checks every lesson it lists) or single lesson files.
+ * Returns the process exit code.
+ */
+pub fn run(args: Vec<String>) -> i32 {
+    run_check("lint-lessons", args, lint_lesson)
+}
+
+/**
+ * Reports the cells of written conjugation and declension tables that the morphology engine doesn't agree with,
+ * see crosscheck::cross_check. Takes the same files as run.
+ */
+pub fn run_paradigm_check(args: Vec<String>) -> i32 {
+    run_check("check-paradigms", args, paradigm_findings)
+}
+
+fn run_check(name: &str, args: Vec<String>, check: fn(&Lesson) -> Vec<Finding>) -> i32 {
+    if args.is_empty() {
+        eprintln!("usage: {} <index.json | lesson.json>...", name);
+        return 2;
+    }
+
+    let mut problems = 0;
+    let mut lesson_paths: HashMap<String, String> = HashMap::new(); // across the lesson files given directly
+    for arg in args {
+        let file = PathBuf::from(arg);
+        let findings = match read(&file) {
+            Ok(source) if source.value_is_index() => lint_index(&file, &source, check),
+            Ok(source) => lint_lesson_file(&source, check).map(|(lesson, mut findings)| {
+                if let Some(other) = lesson_paths.insert(lesson.path.clone(), source.name.clone()) {
+                    findings.push(Finding::new("path", format!("duplicate lesson path \"{}\" (also in {})", lesson.path, other)));
+                }
+                vec![(source, findings)]
+            }).unwrap_or_else(|err| vec![err]),
+            Err(message) => {
+                eprintln!("{}: {}", file.display(), message);
+                problems += 1;
+                continue;
+            },
+        };
+        for (source, findings) in findings {
+            for finding in findings {
+                println!("{}: {}", source.locate(&finding.path), finding.message);
+                problems += 1;
+            }
+        }
+    }
+
+    if problems > 0 {
+        println!("{} problem{}", problems, if problems == 1 { "" } else { "s" });
+        1
+    } else {
+        0
+    }
+}
+
+/// a mistake at a json path like "exercises[2].table_layout.table[3][1]"
+struct Finding {
+    path: String,
+    message: String,
+}
+
+impl Finding {
+    fn new(path: impl Into<String>, message: String) -> Self {
+        Self { path: path.into(), message }
+    }
+}
+
+#[derive(Clone)]
+struct Source {
+    name: String,
+    text: String,
+    positions: HashMap<String, usize>, // json path -> byte offset
+}
+
+impl Source {
+
+    fn value_is_index(&self) -> bool {
+        self.positions.contains_key("courses")
+    }
+
+    /// "file:line:col" of the path, or of the closest parent that has a position
+    fn locate(&self, path: &str) -> String {
+        let mut path = path;
+        let offset = loop {
+            if let Some(offset) = self.positions.get(path) {
+                break *offset;
+            }
+            match path.rfind(['.', '[']) {
+                Some(i) => path = &path[..i],
+                None => break 0,
+            }
+        };
+        let (line, col) = line_col(&self.text, offset);
+        format!("{}:{}:{}", self.name, line, col)
+    }
+
+    fn parse<T: DeserializeOwned>(&self) -> Result<T, Finding> {
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(self.text.as_str()))
+            .map_err(|err| Finding::new(err.path().to_string(), err.inner().to_string()))
+    }
+
+}
+
+fn read(file: &Path) -> Result<Source, String> {
+    let text = fs::read_to_string(file).map_err(|err| err.to_string())?;
+    let mut positions = HashMap::new();
+    if let Err(err) = serde_json::from_str::<serde_json::Value>(text.as_str()) {
+        return Err(format!("{}:{}: {}", err.line(), err.column(), err));
+    }
+    JsonScanner { text: text.as_bytes(), at: 0, positions: &mut positions }.value(String::new());
+    Ok(Source { name: file.display().to_string(), text, positions })
+}
+
+fn lint_index(file: &Path, source: &Source, check: fn(&Lesson) -> Vec<Finding>) -> Vec<(Source, Vec<Finding>)> {
+    let index: CourseIndex = match source.parse() {
+        Ok(index) => index,
+        Err(finding) => return vec![(source.clone(), vec![finding])],
+    };
+    let dir = file.parent().unwrap_or(Path::new("."));
+    let mut results = vec![];
+    let mut index_findings = vec![];
+
+    for (c, course) in index.courses.iter().enumerate() {
+        let mut seen: HashMap<&String, usize> = HashMap::new();
+        for (l, lesson_path) in course.lessons.iter().enumerate() {
+            let path = format!("courses[{}].lessons[{}]", c, l);
+            if let Some(first) = seen.insert(lesson_path, l) {
+                index_findings.push(Finding::new(path.clone(), format!("duplicate lesson path \"{}\" (also lessons[{}])", lesson_path, first)));
+            }
+            let lesson_file = dir.join(&course.path).join(format!("{}.json", lesson_path));
+            match read(&lesson_file) {
+                Ok(lesson_source) => {
+                    match lint_lesson_file(&lesson_source, check) {
+                        Ok((lesson, mut findings)) => {
+                            if lesson.path != *lesson_path {
+                                findings.push(Finding::new("path", format!("lesson path \"{}\" doesn't match \"{}\" in the index", lesson.path, lesson_path)));
+                            }
+                            results.push((lesson_source, findings));
+                        },
+                        Err(result) => results.push(result),
+                    }
+                },
+                Err(message) => index_findings.push(Finding::new(path, format!("{}: {}", lesson_file.display(), message))),
+            }
+        }
+    }
+
+    results.insert(0, (source.clone(), index_findings));
+    results
+}
+
+fn lint_lesson_file(source: &Source, check: fn(&Lesson) -> Vec<Finding>) -> Result<(Lesson, Vec<Finding>), (Source, Vec<Finding>)> {
+    match source.parse::<Lesson>() {
+        Ok(lesson) => {
+            let findings = check(&lesson);
+            Ok((lesson, findings))
+        },
+        Err(finding) => Err((source.clone(), vec![finding])),
+    }
+}
+
+fn lint_lesson(lesson: &Lesson) -> Vec<Finding> {
+    let mut findings = vec![];
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (e, exercise) in lesson.exercises.iter().enumerate() {
+        let path = format!("exercises[{}]", e);
+        // untitled exercises are only text and can't be linked to
+        if exercise.path.is_some() || exercise.title.is_some() {
+            if let Some(first) = seen.insert(exercise.effective_path(), e) {
+                findings.push(Finding::new(path.clone(), format!("duplicate exercise path \"{}\" (also exercises[{}])", exercise.effective_path(), first)));
+            }
+        }
+        if let Some(table_layout) = &exercise.table_layout {
+            lint_table(format!("{}.table_layout", path), table_layout, &mut findings);
+        }
+        if let Some(Err(message)) = exercise.paradigm.as_ref().map(|paradigm| paradigm.table_layout()) {
+            findings.push(Finding::new(format!("{}.paradigm", path), message));
+        }
+    }
+    findings
+}
+
+fn paradigm_findings(lesson: &Lesson) -> Vec<Finding> {
+    lesson.exercises.iter().enumerate()
+        .filter_map(|(e, exercise)| cross_check(exercise).map(|check| (e, check)))
+        .flat_map(|(e, check)| check.mismatches.into_iter().map(move |mismatch| Finding::new(
+            format!("exercises[{}].table_layout.table[{}][{}]", e, mismatch.location.0, mismatch.location.1),
+            format!("\"{}\" isn't a form of {}, expected \"{}\"", mismatch.written, check.paradigm, mismatch.expected),
+        )))
+        .collect()
+}
+
+fn lint_table(path: String, table_layout: &TableLayout, findings: &mut Vec<Finding>) {
+    let table = &table_layout.table;
+    let width = table.first().map(|row| row.len()).unwrap_or(0);
+
+    for (r, row) in table.iter().enumerate() {
+        if row.len() != width {
+            findings.push(Finding::new(format!("{}.table[{}]", path, r), format!("row has {} cells but the first row has {}", row.len(), width)));
+        }
+        for (c, cell) in row.iter().enumerate() {
+            let bars = cell.matches('|').count();
+            if bars != 0 && bars != 2 {
+                findings.push(Finding::new(format!("{}.table[{}][{}]", path, r, c), format!("{} | markers in \"{}\", should be 0 or 2", bars, cell)));
+            }
+        }
+    }
+
+    if let Some(key_col) = table_layout.key_col {
+        let max_width = table.iter().map(|row| row.len()).max().unwrap_or(0);
+        if key_col >= max_width {
+            findings.push(Finding::new(format!("{}.key_col", path), format!("key_col {} is out of range for {} columns", key_col, max_width)));
+        }
+    }
+
+    let parsed_table = create_parsed_table(table);
+    let location_table = create_location_table(table);
+    let interactive = parsed_table.iter().flatten().any(|cell| cell.is_interactive());
+
+    if let Some(options_style_type) = &table_layout.options_style_type {
+        let message = match options_style_type {
+            OptionsStyleType::Disabled => None,
+            _ if !interactive => Some("there are no |answers| to make options from"),
+            OptionsStyleType::ByCol if predict_options_style_type(None, &parsed_table, &location_table) != OptionsStyleType::ByCol =>
+                Some("ByCol needs the answers to form a grid that spans the table's width"),
+            _ if create_options_style(Some(options_style_type.clone()), &parsed_table, &location_table) == DropDownOptionsStyle::Disabled =>
+                Some("there's only one answer to choose from"),
+            _ => None,
+        };
+        if let Some(message) = message {
+            findings.push(Finding::new(format!("{}.options_style_type", path), format!("unsupported options_style_type: {}", message)));
+        }
+    }
+
+    if let Some(default_mode) = &table_layout.default_mode {
+        let options_style = create_options_style(table_layout.options_style_type.clone(), &parsed_table, &location_table);
+        let message = match default_mode {
+            ExerciseMode::Disabled | ExerciseMode::Show => None,
+            _ if !interactive => Some("the table has no |answers|"),
+            ExerciseMode::MultipleChoice if !default_mode.works_with(&options_style) => Some("multiple choice needs ByCol options"),
+            ExerciseMode::Match if match_columns(&parsed_table, &location_table, table_layout.key_col).is_none() => Some("matching needs two columns of answers"),
+            mode if !mode.works_with(&options_style) => Some("the table's options style is Disabled"),
+            _ => None,
+        };
+        if let Some(message) = message {
+            findings.push(Finding::new(format!("{}.default_mode", path), format!("unsupported default_mode {:?}: {}", default_mode, message)));
+        }
+    }
+}
+
+fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) + 1;
+    (line, col)
+}
+
+/**
+ * Records where each value starts, by the same paths serde_path_to_error uses.
+ * Only run on text that already parsed as json.
+ */
+struct JsonScanner<'a> {
+    text: &'a [u8],
+    at: usize,
+    positions: &'a mut HashMap<String, usize>,
+}
+
+impl JsonScanner<'_> {
+
+    fn value(&mut self, path: String) {
+        self.skip_whitespace();
+        self.positions.insert(path.clone(), self.at);
+        match self.text.get(self.at) {
+            Some(b'{') => {
+                self.at += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.text.get(self.at) {
+                        Some(b'}') | None => { self.at += 1; break; },
+                        Some(b',') => { self.at += 1; continue; },
+                        _ => {},
+                    }
+                    let key = self.string();
+                    self.skip_whitespace();
+                    self.at += 1; // :
+                    self.value(if path.is_empty() { key } else { format!("{}.{}", path, key) });
+                }
+            },
+            Some(b'[') => {
+                self.at += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.text.get(self.at) {
+                        Some(b']') | None => { self.at += 1; break; },
+                        Some(b',') => { self.at += 1; continue; },
+                        _ => {},
+                    }
+                    self.value(format!("{}[{}]", path, index));
+                    index += 1;
+                }
+            },
+            Some(b'"') => { self.string(); },
+            _ => {
+                while self.text.get(self.at).is_some_and(|b| !b",]} \t\r\n".contains(b)) {
+                    self.at += 1;
+                }
+            },
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let start = self.at + 1;
+        self.at += 1;
+        while let Some(b) = self.text.get(self.at) {
+            match b {
+                b'\\' => self.at += 2,
+                b'"' => break,
+                _ => self.at += 1,
+            }
+        }
+        let raw = String::from_utf8_lossy(&self.text[start..self.at.min(self.text.len())]).to_string();
+        self.at += 1;
+        serde_json::from_str(format!("\"{}\"", raw).as_str()).unwrap_or(raw)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.text.get(self.at).is_some_and(|b| b.is_ascii_whitespace()) {
+            self.at += 1;
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(text: &str) -> Source {
+        let mut positions = HashMap::new();
+        JsonScanner { text: text.as_bytes(), at: 0, positions: &mut positions }.value(String::new());
+        Source { name: "lesson.json".to_string(), text: text.to_string(), positions }
+    }
+
+    fn messages(text: &str) -> Vec<String> {
+        findings(text, lint_lesson)
+    }
+
+    fn findings(text: &str, check: fn(&Lesson) -> Vec<Finding>) -> Vec<String> {
+        let (_, findings) = lint_lesson_file(&source(text), check).ok().unwrap();
+        findings.into_iter().map(|f| format!("{}: {}", f.path, f.message)).collect()
+    }
+
+    fn lesson(exercises: &str) -> String {
+        format!(r#"{{ "name": "Lesson", "path": "1", "exercises": [{}] }}"#, exercises)
+    }
+
+    #[test]
+    fn a_good_lesson_has_no_findings() {
+        assert!(messages(&lesson(r#"{ "title": "A", "table_layout": { "table": [["Eng", "Pāli"], ["|body|", "|kāya|"], ["|time|", "|kāla|"]], "default_mode": "DropDown" } }"#)).is_empty());
+    }
+
+    #[test]
+    fn duplicate_exercise_paths() {
+        assert_eq!(messages(&lesson(r#"{ "title": "A" }, { "info": "text" }, { "info": "text" }, { "path": "A" }"#)),
+                   vec!["exercises[3]: duplicate exercise path \"A\" (also exercises[0])"]);
+    }
+
+    #[test]
+    fn unbalanced_bars_and_ragged_rows() {
+        assert_eq!(messages(&lesson(r#"{ "table_layout": { "table": [["a", "|b"], ["|c|"]] } }"#)), vec![
+            "exercises[0].table_layout.table[0][1]: 1 | markers in \"|b\", should be 0 or 2",
+            "exercises[0].table_layout.table[1]: row has 1 cells but the first row has 2",
+        ]);
+    }
+
+    #[test]
+    fn key_col_out_of_range() {
+        assert_eq!(messages(&lesson(r#"{ "table_layout": { "table": [["a", "b"]], "key_col": 2 } }"#)),
+                   vec!["exercises[0].table_layout.key_col: key_col 2 is out of range for 2 columns"]);
+    }
+
+    #[test]
+    fn unsupported_modes_and_options() {
+        assert_eq!(messages(&lesson(r#"{ "table_layout": { "table": [["a", "b"]], "default_mode": "ClickReveal", "options_style_type": { "type": "All" } } }"#)), vec![
+            "exercises[0].table_layout.options_style_type: unsupported options_style_type: there are no |answers| to make options from",
+            "exercises[0].table_layout.default_mode: unsupported default_mode ClickReveal: the table has no |answers|",
+        ]);
+        assert_eq!(messages(&lesson(r#"{ "table_layout": { "table": [["|a|", "b"]], "default_mode": "TypeField" } }"#)),
+                   vec!["exercises[0].table_layout.default_mode: unsupported default_mode TypeField: the table's options style is Disabled"]);
+        assert_eq!(messages(&lesson(r#"{ "table_layout": { "table": [["", "sg."], ["1st", "|bhavāmi|"], ["2nd", "|bhavasi|"]], "default_mode": "MultipleChoice" } }"#)),
+                   vec!["exercises[0].table_layout.default_mode: unsupported default_mode MultipleChoice: multiple choice needs ByCol options"]);
+        assert_eq!(messages(&lesson(r#"{ "table_layout": { "table": [["|a|", "|b|", "|c|"], ["|d|", "|e|", "|f|"]], "default_mode": "Match" } }"#)),
+                   vec!["exercises[0].table_layout.default_mode: unsupported default_mode Match: matching needs two columns of answers"]);
+        assert_eq!(messages(&lesson(r#"{ "table_layout": { "table": [["|a|", "b"], ["c", "|d|"]], "options_style_type": { "type": "ByCol" } } }"#)),
+                   vec!["exercises[0].table_layout.options_style_type: unsupported options_style_type: ByCol needs the answers to form a grid that spans the table's width"]);
+    }
+
+    #[test]
+    fn paradigms_that_cant_be_made() {
+        assert_eq!(messages(&lesson(r#"{ "paradigm": { "type": "Conjugation", "stem": "bhav", "class": 0, "tense": "Future" } }"#)),
+                   vec!["exercises[0].paradigm: unknown conjugation class 0"]);
+    }
+
+    #[test]
+    fn paradigm_mismatches_are_at_their_cell() {
+        let lesson = lesson(r#"{ "title": "A", "categories": ["conjugation"], "table_layout": { "table": [["person", "singular", "plural"], ["3rd", "dese|ti|", "dese|nti|"], ["2nd", "dese|si|", "dese|ta|"]] } }"#);
+        assert_eq!(findings(&lesson, paradigm_findings),
+                   vec!["exercises[0].table_layout.table[2][2]: \"dese|ta|\" isn't a form of des- class 7 Present, expected \"dese|tha|\""]);
+        assert!(messages(&lesson).is_empty());
+    }
+
+    #[test]
+    fn findings_are_located_at_their_value() {
+        let source = source("{\n  \"name\": \"Lesson\",\n  \"exercises\": [\n    { \"title\": \"A\" },\n    { \"title\": \"B\" }\n  ]\n}");
+        assert_eq!(source.locate("exercises[1]"), "lesson.json:5:5");
+        assert_eq!(source.locate("exercises[1].title"), "lesson.json:5:16");
+        assert_eq!(source.locate("exercises[1].table_layout.key_col"), "lesson.json:5:5"); // closest parent
+        assert_eq!(source.locate("name"), "lesson.json:2:11");
+    }
+
+    #[test]
+    fn escaped_keys_are_unescaped() {
+        let source = source(r#"{ "a\"b": [1, {"c": true}] }"#);
+        assert!(source.positions.contains_key("a\"b[1].c"));
+    }
+
+    #[test]
+    fn type_errors_are_findings_at_their_path() {
+        let (_, findings) = lint_lesson_file(&source(&lesson(r#"{ "table_layout": { "table": [["a"]], "default_mode": "Nope" } }"#)), lint_lesson).err().unwrap();
+        assert_eq!(findings[0].path, "exercises[0].table_layout.default_mode");
+    }
+}
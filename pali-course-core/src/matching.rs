@@ -0,0 +1,61 @@
+use rand::seq::SliceRandom;
+
+use crate::options::{predict_options_style_type, OptionsStyleType};
+use crate::seed::seeded_rng;
+use crate::table::{count_header_rows, Location, ParsedTable};
+
+/**
+ * (key column, pool column) for matching pairs, when every row has two cells and they're all answers.
+ * The key column is key_col, or the first one.
+ */
+pub fn match_columns(parsed_table: &ParsedTable, location_table: &[Vec<Location>], key_col: Option<usize>) -> Option<(usize, usize)> {
+    let two_columns = !parsed_table.is_empty() && parsed_table.iter().all(|row| row.len() == 2);
+    if !two_columns || predict_options_style_type(None, parsed_table, location_table) != OptionsStyleType::ByCol {
+        return None;
+    }
+    let key = key_col.filter(|col: &usize| *col < 2).unwrap_or(0);
+    Some((key, 1 - key))
+}
+
+/// the pool column's answers in an order made from the seed, by where they are in the table
+pub fn match_pool(parsed_table: &ParsedTable, pool_col: usize, seed: u64) -> Vec<Location> {
+    let mut pool: Vec<Location> = (count_header_rows(parsed_table)..parsed_table.len())
+        .map(|row| (row, pool_col))
+        .filter(|(row, col)| parsed_table[*row].get(*col).is_some_and(|cell| cell.is_interactive()))
+        .collect();
+    pool.shuffle(&mut seeded_rng(seed));
+    pool
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::{create_location_table, create_parsed_table, table};
+
+    fn columns(rows: &[&[&str]], key_col: Option<usize>) -> Option<(usize, usize)> {
+        let table = table(rows);
+        match_columns(&create_parsed_table(&table), &create_location_table(&table), key_col)
+    }
+
+    #[test]
+    fn two_columns_of_answers_can_be_matched() {
+        let rows: &[&[&str]] = &[&["noun", "meaning"], &["|kāya|", "|body|"], &["|kāla|", "|time|"]];
+        assert_eq!(columns(rows, None), Some((0, 1)));
+        assert_eq!(columns(rows, Some(1)), Some((1, 0)));
+    }
+
+    #[test]
+    fn other_tables_cant() {
+        assert_eq!(columns(&[&["he is", "meaning"], &["ahosi", "|there is|"], &["bhavati", "|is|"]], None), None);
+        assert_eq!(columns(&[&["|a|", "|b|", "|c|"], &["|d|", "|e|", "|f|"]], None), None);
+    }
+
+    #[test]
+    fn pool_is_the_other_columns_answers_shuffled_by_seed() {
+        let parsed = create_parsed_table(&table(&[&["noun", "meaning"], &["|kāya|", "|body|"], &["|kāla|", "|time|"], &["|rūpa|", "|form|"]]));
+        let mut pool = match_pool(&parsed, 1, 3);
+        assert_eq!(match_pool(&parsed, 1, 3), pool);
+        pool.sort();
+        assert_eq!(pool, vec![(1, 1), (2, 1), (3, 1)]);
+    }
+}
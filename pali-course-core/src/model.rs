@@ -0,0 +1,442 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::check::{AnswerCheck, CheckStrictness};
+use crate::conjugation::Conjugation;
+use crate::declension::Declension;
+use crate::prosody::Syllables;
+use crate::sandhi::Sandhi;
+use crate::options::{DropDownOptionsStyle, OptionsStyleType};
+use crate::table::DataTable;
+use ExerciseMode::*;
+
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct Courses {
+    pub courses: Vec<Course>
+}
+
+impl Courses {
+    pub fn get(&self, path: &str) -> Option<&Course> {
+        self.courses.iter().find(|c: &&Course| c.path == path)
+    }
+
+    /// gives every exercise its id, which is what its progress and review cards are saved under
+    pub fn assign_ids(&mut self) {
+        for course in &mut self.courses {
+            for lesson in &mut course.lessons {
+                for exercise in &mut lesson.exercises {
+                    exercise.id = format!("{}/{}-{}", course.path, lesson.path, exercise.title.clone().unwrap_or("anon".to_string()));
+                }
+            }
+        }
+    }
+
+    /**
+     * Gives exercises with a paradigm and no table of their own the paradigm's table, and its explanation when they
+     * don't have one. Ones that can't be made are in Course::warnings
+     */
+    pub fn expand_paradigms(&mut self) {
+        for exercise in self.courses.iter_mut().flat_map(|c| &mut c.lessons).flat_map(|l| &mut l.exercises) {
+            let Some(paradigm) = &exercise.paradigm else { continue };
+            if exercise.table_layout.is_none() {
+                exercise.table_layout = paradigm.table_layout().ok();
+            }
+            if exercise.explanation.is_none() {
+                exercise.explanation = paradigm.explanation();
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct Course {
+    pub name: String,
+    pub path: String,
+    pub reference: Option<BookReference>,
+    #[serde(default)]
+    pub categories: Vec<ExerciseCategory>,
+    pub lessons: Vec<Lesson>
+}
+
+impl Course {
+
+    pub fn category(&self, id: &str) -> Option<&ExerciseCategory> {
+        self.categories.iter().find(|c: &&ExerciseCategory| c.id == id)
+    }
+
+    pub fn category_mode(&self, exercise: &Exercise) -> Option<ExerciseMode> {
+        exercise.category_mode(&self.categories)
+    }
+
+    /**
+     * Mistakes in the lesson data that are skipped over instead of breaking the course
+     */
+    pub fn warnings(&self) -> Vec<String> {
+        self.lessons.iter()
+            .flat_map(|lesson: &Lesson| lesson.exercises.iter().map(move |exercise: &Exercise| (lesson, exercise)))
+            .flat_map(|(lesson, exercise)| {
+                let unknown_categories = exercise.categories.iter().flatten()
+                    .filter(|id: &&String| self.category(id).is_none())
+                    .map(|id: &String| format!("unknown category \"{}\"", id));
+                let paradigm = exercise.paradigm.as_ref().and_then(|paradigm| paradigm.table_layout().err());
+                unknown_categories.chain(paradigm)
+                    .map(move |problem| format!("{} lesson \"{}\", exercise \"{}\": {}", self.path, lesson.path, exercise.effective_path(), problem))
+                    .collect::<Vec<String>>()
+            })
+            .collect()
+    }
+
+}
+
+/// the fetched index, where each course lists its lessons by path. A lesson is in "{course path}/{lesson path}.json" next to the index
+#[derive(Deserialize)]
+pub struct CourseIndex {
+    pub courses: Vec<CourseEntry>
+}
+
+#[derive(Deserialize)]
+pub struct CourseEntry {
+    pub name: String,
+    pub path: String,
+    pub reference: Option<BookReference>,
+    #[serde(default)]
+    pub categories: Vec<ExerciseCategory>,
+    pub lessons: Vec<String>
+}
+
+impl CourseEntry {
+    pub fn into_course(self, lessons: Vec<Lesson>) -> Course {
+        Course { name: self.name, path: self.path, reference: self.reference, categories: self.categories, lessons }
+    }
+}
+
+/**
+ * The book a course follows. Exercises link to their "page" in it.
+ */
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct BookReference {
+    pub name: String,
+    pub base_url: String, // the page number is appended to this
+    pub page_offset: i32, // pages before the book's numbering starts, like a preface
+    #[serde(default)]
+    pub suffix: String, // comes after the page number, like a viewer mode
+}
+
+impl BookReference {
+    pub fn page_url(&self, page: i32) -> String {
+        format!("{}{}{}", self.base_url, page + self.page_offset, self.suffix)
+    }
+}
+
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct Lesson {
+    pub name: String,
+    pub path: String,
+    pub exercises: Vec<Exercise>
+}
+
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct Exercise {
+    #[serde(skip)]
+    pub id: String, // "{course path}/{lesson path}-{title}", from Courses::assign_ids
+    pub exercise_level: Option<String>,
+    pub categories: Option<Vec<String>>, // ids of the course's categories
+    pub info: Option<String>,
+    pub title: Option<String>,
+    pub path: Option<String>, // how to refer to it in the url
+    pub table_layout: Option<TableLayout>,
+    pub generated: Option<GeneratedTable>, // replaces table_layout
+    pub paradigm: Option<Paradigm>, // makes table_layout when it's left out, from Courses::expand_paradigms
+    pub explanation: Option<String>,
+    pub page: Option<i32>,
+}
+
+impl Exercise {
+
+    pub fn effective_path(&self) -> String {
+        // .map(|mut t: String| {t.remove_matches(|c: char| c.is_whitespace()); t})
+        self.path.clone().or(self.title.clone()).unwrap_or("404".to_string())
+    }
+
+    pub fn is_in(&self, category: &ExerciseCategory) -> bool {
+        self.categories.as_ref().is_some_and(|ids: &Vec<String>| ids.contains(&category.id))
+    }
+
+    /// the default mode of the first of its categories that has one
+    pub fn category_mode(&self, categories: &[ExerciseCategory]) -> Option<ExerciseMode> {
+        categories.iter()
+            .filter(|c: &&ExerciseCategory| self.is_in(c))
+            .find_map(|c: &ExerciseCategory| c.default_mode.clone())
+    }
+
+}
+
+/**
+ * Declared by each course. Exercises refer to them by id.
+ */
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct ExerciseCategory {
+    pub id: String, // how to refer to it in the url
+    pub name: String,
+    pub description: Option<String>,
+    pub default_mode: Option<ExerciseMode>, // for its tables that don't set their own
+}
+
+/**
+ * Tables made by the site instead of written out in the json
+ */
+#[derive(PartialEq, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum GeneratedTable {
+    InputScheme, // the chosen input scheme's letters
+    Script, // a few words in the chosen script
+}
+
+/**
+ * Tables worked out from a stem, words or pairs of words instead of written out in the json. Unlike generated tables they're
+ * the same for everyone, so they're reviewed and quizzed like written ones.
+ */
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum Paradigm {
+    Conjugation(Conjugation),
+    Declension(Declension),
+    Syllables(Syllables),
+    Sandhi(Sandhi),
+}
+
+impl Paradigm {
+    pub fn table_layout(&self) -> Result<TableLayout, String> {
+        match self {
+            Paradigm::Conjugation(conjugation) => conjugation.table_layout(),
+            Paradigm::Declension(declension) => declension.table_layout(),
+            Paradigm::Syllables(syllables) => syllables.table_layout(),
+            Paradigm::Sandhi(sandhi) => sandhi.table_layout(),
+        }
+    }
+
+    /// why the answers are right, for the ones that can say
+    pub fn explanation(&self) -> Option<String> {
+        match self {
+            Paradigm::Syllables(syllables) => syllables.explanation().ok(),
+            Paradigm::Sandhi(sandhi) => sandhi.explanation().ok(),
+            Paradigm::Conjugation(_) | Paradigm::Declension(_) => None,
+        }
+    }
+}
+
+impl Display for Paradigm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Paradigm::Conjugation(c) => write!(f, "{}- class {} {:?}", c.stem.trim_end_matches('-'), c.class, c.tense),
+            Paradigm::Declension(d) => write!(f, "{}- {:?} stem {:?}", d.stem.trim_end_matches('-'), d.stem_class, d.gender),
+            Paradigm::Syllables(s) => write!(f, "the syllables of {}", s.words.join(", ")),
+            Paradigm::Sandhi(s) => write!(f, "the sandhi of {}", s.pairs.iter().map(|[first, second]| format!("{} + {}", first, second)).collect::<Vec<String>>().join(", ")),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Default, Deserialize, Debug)]
+pub struct TableLayout {
+    pub table: DataTable,
+    pub key_col: Option<usize>, // Show when rows unordered
+    pub shuffle_rows: Option<bool>,
+    pub default_mode: Option<ExerciseMode>, // Default: Censor
+    pub options_style_type: Option<OptionsStyleType>, // predicted
+    pub check_strictness: Option<CheckStrictness>, // Default: Exact
+    pub answer_check: Option<AnswerCheck>, // Default: the written answers
+    pub pali_cols: Option<Vec<usize>>, // shown in the chosen script, below the header rows
+}
+
+impl TableLayout {
+    pub fn new(table: DataTable) -> Self {
+        Self { table, ..Self::default() }
+    }
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub enum ExerciseMode {
+    Disabled,
+    Show, // ABC
+    HoverReveal, // [][][]
+    ClickReveal, // [] -> A
+    CensorByLetter, // A[][] -> AB[]
+    TypeField, // [Az ]
+    DropDown, // >ABC or >XYZ
+    MultipleChoice, // [A] [X] [Y] [Z], checked when clicked
+    Match, // A [] + pool of X Y Z, for two columns
+    Flashcards, // one row at a time, A -> BC
+}
+
+impl ExerciseMode {
+
+    pub fn is_resettable(&self) -> bool {
+        matches!(self, ClickReveal | CensorByLetter | TypeField | DropDown | MultipleChoice | Match | Flashcards)
+    }
+
+    pub fn has_input(&self) -> bool {
+        matches!(self, TypeField | DropDown)
+    }
+
+    /**
+     * Whether a table with these options can be done this way; multiple choice takes its distractors from the answer's column.
+     * Match also needs the table to be two columns, see matching::match_columns.
+     */
+    pub fn works_with(&self, options_style: &DropDownOptionsStyle) -> bool {
+        match self {
+            MultipleChoice | Match => matches!(options_style, DropDownOptionsStyle::ByCol { .. }),
+            mode => !mode.has_input() || *options_style != DropDownOptionsStyle::Disabled,
+        }
+    }
+
+}
+
+impl FromStr for ExerciseMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Show"           => Ok(Show),
+            "HoverReveal"    => Ok(HoverReveal),
+            "ClickReveal"    => Ok(ClickReveal),
+            "CensorByLetter" => Ok(CensorByLetter),
+            "TypeField"      => Ok(TypeField),
+            "DropDown"       => Ok(DropDown),
+            "MultipleChoice" => Ok(MultipleChoice),
+            "Match"          => Ok(Match),
+            "Flashcards"     => Ok(Flashcards),
+            "Disabled"       => Ok(Disabled),
+            _ => Err(())
+        }
+    }
+}
+
+impl Display for ExerciseMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Show =>           "Show",
+            HoverReveal =>    "HoverReveal",
+            ClickReveal =>    "ClickReveal",
+            CensorByLetter => "CensorByLetter",
+            TypeField =>      "TypeField",
+            DropDown =>       "DropDown",
+            MultipleChoice => "MultipleChoice",
+            Match =>          "Match",
+            Flashcards =>     "Flashcards",
+            Disabled =>       "Disabled",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn course() -> Course {
+        serde_json::from_str(r#"{
+            "name": "Course",
+            "path": "course",
+            "reference": { "name": "Book", "base_url": "https://example.com/page/n", "page_offset": 13, "suffix": "/mode/1up" },
+            "categories": [
+                { "id": "conjugation", "name": "Conjugations", "default_mode": "HoverReveal" },
+                { "id": "vocab", "name": "Vocab" }
+            ],
+            "lessons": [{
+                "name": "Lesson 1",
+                "path": "1",
+                "exercises": [
+                    { "title": "Verbs", "categories": ["vocab", "conjugation"] },
+                    { "title": "Nouns", "categories": ["nouns"] },
+                    { "info": "only text" }
+                ]
+            }]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn page_url_adds_offset() {
+        assert_eq!(course().reference.unwrap().page_url(5), "https://example.com/page/n18/mode/1up");
+    }
+
+    #[test]
+    fn effective_path_falls_back_to_title() {
+        let course = course();
+        let exercises = &course.lessons[0].exercises;
+        assert_eq!(exercises[0].effective_path(), "Verbs");
+        assert_eq!(exercises[2].effective_path(), "404");
+    }
+
+    #[test]
+    fn category_mode_comes_from_a_category_that_has_one() {
+        let course = course();
+        let exercises = &course.lessons[0].exercises;
+        assert_eq!(course.category_mode(&exercises[0]), Some(HoverReveal));
+        assert_eq!(course.category_mode(&exercises[1]), None);
+    }
+
+    #[test]
+    fn unknown_categories_are_warnings() {
+        let warnings = course().warnings();
+        assert_eq!(warnings, vec!["course lesson \"1\", exercise \"Nouns\": unknown category \"nouns\"".to_string()]);
+    }
+
+    #[test]
+    fn ids_are_from_the_lesson_the_exercise_is_in() {
+        let mut courses = Courses { courses: vec![course()] };
+        courses.assign_ids();
+        let ids: Vec<String> = courses.courses[0].lessons[0].exercises.iter().map(|e| e.id.clone()).collect();
+        assert_eq!(ids, vec!["course/1-Verbs", "course/1-Nouns", "course/1-anon"]);
+    }
+
+    #[test]
+    fn paradigms_fill_in_missing_tables() {
+        let mut courses: Courses = serde_json::from_str(r#"{ "courses": [{ "name": "Course", "path": "course", "lessons": [{ "name": "Lesson 1", "path": "1", "exercises": [
+            { "title": "Present", "paradigm": { "type": "Conjugation", "stem": "bhav-", "class": 1, "tense": "Present" } },
+            { "title": "Written", "paradigm": { "type": "Conjugation", "stem": "bhav-", "class": 1, "tense": "Present" }, "table_layout": { "table": [["|bhavati|"]] } },
+            { "title": "Broken", "paradigm": { "type": "Conjugation", "stem": "bhav-", "class": 8, "tense": "Present" } }
+        ]}]}]}"#).unwrap();
+        courses.expand_paradigms();
+        let exercises = &courses.courses[0].lessons[0].exercises;
+        assert_eq!(exercises[0].table_layout.as_ref().map(|t| t.table[1][1].as_str()), Some("bhav|ati|"));
+        assert_eq!(exercises[1].table_layout.as_ref().map(|t| t.table.len()), Some(1));
+        assert_eq!(exercises[2].table_layout, None);
+        assert_eq!(courses.courses[0].warnings(), vec!["course lesson \"1\", exercise \"Broken\": unknown conjugation class 8".to_string()]);
+    }
+
+    #[test]
+    fn paradigms_bring_their_explanation() {
+        let mut courses: Courses = serde_json::from_str(r#"{ "courses": [{ "name": "Course", "path": "course", "lessons": [{ "name": "Lesson 1", "path": "1", "exercises": [
+            { "title": "Syllables", "paradigm": { "type": "Syllables", "words": ["saṃ"] } },
+            { "title": "Explained", "paradigm": { "type": "Syllables", "words": ["saṃ"] }, "explanation": "written" }
+        ]}]}]}"#).unwrap();
+        courses.expand_paradigms();
+        let exercises = &courses.courses[0].lessons[0].exercises;
+        assert_eq!(exercises[0].explanation.as_deref(), Some("saṃ (saṃ): a is short before ṃ, but ṃ makes the syllable long."));
+        assert_eq!(exercises[1].explanation.as_deref(), Some("written"));
+    }
+
+    #[test]
+    fn exercise_mode_round_trips_through_strings() {
+        for mode in [Disabled, Show, HoverReveal, ClickReveal, CensorByLetter, TypeField, DropDown, MultipleChoice, Match, Flashcards] {
+            assert_eq!(ExerciseMode::from_str(mode.to_string().as_str()), Ok(mode));
+        }
+    }
+
+    #[test]
+    fn multiple_choice_needs_options_by_column() {
+        let by_col = DropDownOptionsStyle::ByCol { col_options: vec![] };
+        let all = DropDownOptionsStyle::All { options: vec![] };
+        assert!(MultipleChoice.works_with(&by_col));
+        assert!(!MultipleChoice.works_with(&all));
+        assert!(DropDown.works_with(&all) && !DropDown.works_with(&DropDownOptionsStyle::Disabled));
+        assert!(ClickReveal.works_with(&DropDownOptionsStyle::Disabled));
+    }
+
+    #[test]
+    fn table_layout_fields_are_optional() {
+        let layout: TableLayout = serde_json::from_str(r#"{ "table": [["a", "|b|"]] }"#).unwrap();
+        assert_eq!(layout, TableLayout::new(vec![vec!["a".to_string(), "|b|".to_string()]]));
+    }
+}
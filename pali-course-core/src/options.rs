@@ -0,0 +1,203 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use crate::table::{DirectionalInclusive, GetLocation, Location, ParsedCell, ParsedTable};
+
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+#[serde(tag = "type", content = "options")]
+/// for DropDown mode
+pub enum OptionsStyleType {
+    Disabled,
+    All,
+    ByCol,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+#[serde(tag = "type", content = "options")]
+/// for DropDown mode
+pub enum DropDownOptionsStyle {
+    Disabled,
+    All { options: Vec<String> },
+    ByCol { col_options: Vec<Vec<String>> },
+}
+
+impl FromStr for OptionsStyleType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Disabled" => Ok(OptionsStyleType::Disabled),
+            "All" =>      Ok(OptionsStyleType::All),
+            "ByCol" =>    Ok(OptionsStyleType::ByCol),
+            _ =>          Err(()),
+        }
+    }
+}
+
+pub fn create_options_style(options_style_type: Option<OptionsStyleType>, parsed_table: &ParsedTable, location_table: &[Vec<Location>]) -> DropDownOptionsStyle {
+    match predict_options_style_type(options_style_type, parsed_table, location_table) {
+        OptionsStyleType::Disabled => DropDownOptionsStyle::Disabled,
+        OptionsStyleType::All => {
+            let options = create_options(parsed_table.iter().flat_map(|row: &Vec<ParsedCell>| row).collect());
+            if options.len() > 1 { DropDownOptionsStyle::All { options } } else { DropDownOptionsStyle::Disabled }
+        },
+        OptionsStyleType::ByCol => {
+            let max_columns = parsed_table.iter().map(|row| row.len()).max().unwrap_or(0);
+            let mut col_options: Vec<Vec<&ParsedCell>> = vec![Vec::new(); max_columns];
+            for row in parsed_table {
+                for (col_index, cell) in row.iter().enumerate() {
+                    col_options[col_index].push(cell);
+                }
+            }
+            let col_options: Vec<Vec<String>> = col_options.iter().map(|col| create_options(col.to_vec())).collect();
+            DropDownOptionsStyle::ByCol { col_options }
+        }
+    }
+}
+
+pub fn predict_options_style_type(options_style_type: Option<OptionsStyleType>, parsed_table: &ParsedTable, location_table: &[Vec<Location>]) -> OptionsStyleType {
+    if let Some(options_style_type) = options_style_type {
+        return options_style_type;
+    }
+
+    if parsed_table.len() == 1 || parsed_table.iter().filter(|v| v.len() != 1).count() == 0 {
+        return OptionsStyleType::Disabled;
+    }
+
+    let top_left_opt: Option<&Location> = location_table.iter().flatten()
+        .find(|l: &&Location| parsed_table.get_location_unchecked(l).is_interactive());
+    if top_left_opt.is_none() {
+        return OptionsStyleType::Disabled; // no cells are interactive
+    }
+    let top_left = top_left_opt.unwrap();
+
+    let bottom_right: &Location = location_table.iter().flatten()
+        .rev()
+        .find(|l: &&Location| parsed_table.get_location_unchecked(l).is_interactive()).unwrap();
+
+    let extends_horizontally = top_left.1 == 0 && bottom_right.1 == parsed_table.get(bottom_right.0).unwrap().len() - 1;
+
+    if extends_horizontally {
+
+        let forms_a_grid = location_table.iter().flatten().find(|loc: &&Location| {
+            // let loc: Location = *loc_ref.clone();
+            if parsed_table.get_location_unchecked(loc).is_interactive()
+            { loc.left(top_left) || loc.above(top_left) || loc.right(bottom_right) || loc.below(bottom_right) }
+            else
+            { !loc.left(top_left) && !loc.above(top_left) && !loc.right(bottom_right) && !loc.below(bottom_right) }
+        }).is_none();
+
+        if forms_a_grid {
+            return OptionsStyleType::ByCol;
+        }
+    }
+    OptionsStyleType::All
+}
+
+/**
+ * The options most like the answer, so picking it out takes knowing it. Letters of a shared ending ("bhavati", "gacchati")
+ * count the most, then those of a shared beginning, and being a different length counts against. Ties keep the options' order.
+ */
+pub fn rank_distractors(answer: &str, options: &[String], count: usize) -> Vec<String> {
+    let answer: Vec<char> = answer.chars().collect();
+    let mut ranked: Vec<(i32, &String)> = options.iter()
+        .filter(|option: &&String| option.chars().ne(answer.iter().cloned()))
+        .map(|option: &String| {
+            let chars: Vec<char> = option.chars().collect();
+            let suffix = chars.iter().rev().zip(answer.iter().rev()).take_while(|(a, b)| a == b).count() as i32;
+            let prefix = chars.iter().zip(answer.iter()).take_while(|(a, b)| a == b).count() as i32;
+            let length_difference = (chars.len() as i32 - answer.len() as i32).abs();
+            (3 * suffix + 2 * prefix - length_difference, option)
+        })
+        .collect();
+    ranked.sort_by_key(|(score, _)| -score);
+    ranked.into_iter().map(|(_, option)| option.clone()).unique().take(count).collect()
+}
+
+fn create_options(unfiltered_options: Vec<&ParsedCell>) -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    let mut options: Vec<String> = unfiltered_options.iter()
+        .filter(|c: &&&ParsedCell| c.is_interactive())
+        .map(|c: &&ParsedCell| match c { ParsedCell::Interactive(text) => text.middle.clone(), _ => unreachable!() } )
+        .unique()
+        .collect();
+    options.shuffle(&mut rng);
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::{create_location_table, create_parsed_table, table};
+
+    fn predict(rows: &[&[&str]]) -> OptionsStyleType {
+        let table = table(rows);
+        predict_options_style_type(None, &create_parsed_table(&table), &create_location_table(&table))
+    }
+
+    fn style(options_style_type: Option<OptionsStyleType>, rows: &[&[&str]]) -> DropDownOptionsStyle {
+        let table = table(rows);
+        create_options_style(options_style_type, &create_parsed_table(&table), &create_location_table(&table))
+    }
+
+    #[test]
+    fn answers_filling_whole_rows_are_by_column() {
+        assert_eq!(predict(&[&["Eng", "Pāli"], &["|body|", "|kāya|"], &["|time|", "|kāla|"]]), OptionsStyleType::ByCol);
+    }
+
+    #[test]
+    fn answers_in_some_columns_are_all_together() {
+        assert_eq!(predict(&[&["", "sg.", "pl."], &["1st", "|bhavāmi|", "|bhavāma|"], &["2nd", "|bhavasi|", "|bhavatha|"]]), OptionsStyleType::All);
+    }
+
+    #[test]
+    fn answers_that_dont_form_a_grid_are_all_together() {
+        assert_eq!(predict(&[&["|a|", "b"], &["c", "|d|"]]), OptionsStyleType::All);
+    }
+
+    #[test]
+    fn single_rows_or_columns_and_tables_without_answers_are_disabled() {
+        assert_eq!(predict(&[&["|a|", "|b|"]]), OptionsStyleType::Disabled);
+        assert_eq!(predict(&[&["|a|"], &["|b|"]]), OptionsStyleType::Disabled);
+        assert_eq!(predict(&[&["a", "b"], &["c", "d"]]), OptionsStyleType::Disabled);
+    }
+
+    #[test]
+    fn a_given_style_is_kept() {
+        assert_eq!(predict_options_style_type(Some(OptionsStyleType::ByCol), &vec![], &[]), OptionsStyleType::ByCol);
+    }
+
+    #[test]
+    fn all_options_are_the_unique_displayed_answers() {
+        let DropDownOptionsStyle::All { mut options } = style(Some(OptionsStyleType::All), &[&["|a|", "b"], &["c", "|d, e|"], &["|a|", "f"]]) else { panic!() };
+        options.sort();
+        assert_eq!(options, vec!["a", "d, e"]);
+    }
+
+    #[test]
+    fn one_option_is_disabled() {
+        assert_eq!(style(Some(OptionsStyleType::All), &[&["|a|", "b"], &["c", "|a|"]]), DropDownOptionsStyle::Disabled);
+    }
+
+    #[test]
+    fn distractors_are_the_most_similar_options() {
+        let options: Vec<String> = ["kāya", "bhavanti", "gacchati", "bhavati", "hoti", "dhammo"].iter().map(|o| o.to_string()).collect();
+        assert_eq!(rank_distractors("bhavati", &options, 3), vec!["bhavanti", "gacchati", "hoti"]);
+    }
+
+    #[test]
+    fn distractors_leave_out_the_answer_and_run_out() {
+        let options: Vec<String> = ["kāya", "kāla", "kāya"].iter().map(|o| o.to_string()).collect();
+        assert_eq!(rank_distractors("kāya", &options, 3), vec!["kāla"]);
+    }
+
+    #[test]
+    fn by_column_options_are_per_column() {
+        let DropDownOptionsStyle::ByCol { col_options } = style(None, &[&["Eng", "Pāli"], &["|body|", "|kāya|"], &["|time|", "|kāla|"]]) else { panic!() };
+        let sorted: Vec<Vec<String>> = col_options.into_iter().map(|mut col| { col.sort(); col }).collect();
+        assert_eq!(sorted, vec![vec!["body", "time"], vec!["kāla", "kāya"]]);
+    }
+}
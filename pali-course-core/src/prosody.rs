@@ -0,0 +1,227 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::model::{ExerciseMode, TableLayout};
+use crate::table::DataTable;
+
+/**
+ * The "Long or Short" exercise for any words: each word split into syllables, with the length of the vowel and of
+ * the syllable, and why.
+ */
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+pub struct Syllables {
+    pub words: Vec<String>, // in IAST
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Length {
+    Short,
+    Long,
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Length::Short => "short",
+            Length::Long => "long",
+        })
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Syllable {
+    pub text: String,
+    pub vowel: String,
+    pub following: String, // the consonants between the vowel and the next one, some of which can be the next syllable's
+    pub vowel_length: Length,
+    pub weight: Length,
+}
+
+const VOWELS: [&str; 8] = ["a", "ā", "i", "ī", "u", "ū", "e", "o"];
+const CONSONANTS: [&str; 36] = [
+    "kh", "gh", "ch", "jh", "ṭh", "ḍh", "th", "dh", "ph", "bh", "ḷh", // aspirates are one consonant
+    "k", "g", "ṅ", "c", "j", "ñ", "ṭ", "ḍ", "ṇ", "t", "d", "n", "p", "b", "m", "y", "r", "l", "ḷ", "v", "s", "h",
+    "ṃ", "ṁ", "ŋ",
+];
+const NIGGAHITA: [&str; 3] = ["ṃ", "ṁ", "ŋ"];
+
+enum Letter {
+    Vowel(String),
+    Consonant(String),
+}
+
+/// "bhikkhu" -> ["bh", "i", "k", "kh", "u"], so "mh" is two consonants and "bh" one
+fn letters(word: &str) -> Result<Vec<Letter>, String> {
+    let word: String = word.trim().to_lowercase().nfc().collect();
+    let mut rest = word.as_str();
+    let mut letters = vec![];
+    while !rest.is_empty() {
+        let letter = if let Some(vowel) = VOWELS.iter().find(|v| rest.starts_with(*v)) {
+            Letter::Vowel(vowel.to_string())
+        } else if let Some(consonant) = CONSONANTS.iter().find(|c| rest.starts_with(*c)) {
+            Letter::Consonant(consonant.to_string())
+        } else {
+            let unknown = rest.chars().next().unwrap_or_default();
+            return Err(format!("\"{}\" in \"{}\" isn't a Pāli letter", unknown, word));
+        };
+        rest = &rest[match &letter { Letter::Vowel(s) | Letter::Consonant(s) => s.len() }..];
+        letters.push(letter);
+    }
+    Ok(letters)
+}
+
+/**
+ * Splits a word into syllables. Of the consonants between two vowels, ṃ and the first of two or more close the
+ * syllable before them; a single one starts the next.
+ */
+pub fn syllabify(word: &str) -> Result<Vec<Syllable>, String> {
+    let letters = letters(word)?;
+    let vowel_positions: Vec<usize> = letters.iter().enumerate().filter(|(_, l)| matches!(l, Letter::Vowel(_))).map(|(i, _)| i).collect();
+    if vowel_positions.is_empty() {
+        return Err(format!("\"{}\" has no vowels", word));
+    }
+    let text = |range: std::ops::Range<usize>| letters[range].iter().map(|l| match l { Letter::Vowel(s) | Letter::Consonant(s) => s.as_str() }).collect::<String>();
+
+    let mut syllables = vec![];
+    let mut start = 0;
+    for (n, &v) in vowel_positions.iter().enumerate() {
+        let next_vowel = vowel_positions.get(n + 1).copied();
+        let consonants_end = next_vowel.unwrap_or(letters.len());
+        let consonants = consonants_end - v - 1;
+        let niggahita = consonants > 0 && matches!(&letters[v + 1], Letter::Consonant(c) if NIGGAHITA.contains(&c.as_str()));
+        let end = match next_vowel {
+            None => letters.len(), // the rest of the word
+            Some(_) if niggahita => v + 2,
+            Some(_) if consonants >= 2 => v + 2,
+            Some(_) => v + 1,
+        };
+        let Letter::Vowel(vowel) = &letters[v] else { unreachable!() };
+        let closed = end > v + 1;
+        let vowel_length = match vowel.as_str() {
+            "ā" | "ī" | "ū" => Length::Long,
+            "e" | "o" if !closed => Length::Long,
+            _ => Length::Short, // e and o too, before a double consonant or ṃ
+        };
+        syllables.push(Syllable {
+            text: text(start..end),
+            vowel: vowel.clone(),
+            following: text(v + 1..consonants_end),
+            vowel_length,
+            weight: if vowel_length == Length::Long || closed { Length::Long } else { Length::Short },
+        });
+        start = end;
+    }
+    Ok(syllables)
+}
+
+impl Syllable {
+
+    pub(crate) fn closed(&self) -> bool {
+        !self.text.ends_with(self.vowel.as_str())
+    }
+
+    /// why its vowel and it are the lengths they are
+    pub fn explain(&self) -> String {
+        let vowel = &self.vowel;
+        let following = &self.following;
+        match (self.vowel_length, self.closed()) {
+            (Length::Long, _) if vowel == "e" || vowel == "o" => format!("{} is long when it isn't before a double consonant or ṃ, so the syllable is long", vowel),
+            (Length::Long, _) => format!("{} is always long, so the syllable is long", vowel),
+            (Length::Short, true) if NIGGAHITA.iter().any(|n| following.starts_with(n)) => format!("{} is short before ṃ, but ṃ makes the syllable long", vowel),
+            (Length::Short, true) if vowel == "e" || vowel == "o" => format!("{} is short before the double consonant {}, but the syllable is long because of it", vowel, following),
+            (Length::Short, true) => format!("{} is short, but the double consonant {} after it makes the syllable long", vowel, following),
+            (Length::Short, false) if following.is_empty() => format!("{} is short and nothing follows it, so the syllable is short", vowel),
+            (Length::Short, false) => format!("{} is short and {} is one consonant, so the syllable is short", vowel, following),
+        }
+    }
+
+}
+
+impl Syllables {
+
+    fn all(&self) -> Result<Vec<(&str, Syllable)>, String> {
+        let mut all = vec![];
+        for word in &self.words {
+            all.extend(syllabify(word)?.into_iter().map(|syllable| (word.as_str(), syllable)));
+        }
+        Ok(all)
+    }
+
+    /// a row for each syllable, answered with "short" or "long" from drop downs
+    pub fn table_layout(&self) -> Result<TableLayout, String> {
+        let mut table: DataTable = vec![vec!["word".to_string(), "syllable".to_string(), "vowel length".to_string(), "syllable length".to_string()]];
+        for (word, syllable) in self.all()? {
+            table.push(vec![word.to_string(), syllable.text, format!("|{}|", syllable.vowel_length), format!("|{}|", syllable.weight)]);
+        }
+        Ok(TableLayout {
+            pali_cols: Some(vec![0, 1]),
+            default_mode: Some(ExerciseMode::DropDown),
+            ..TableLayout::new(table)
+        })
+    }
+
+    /// a sentence for each syllable
+    pub fn explanation(&self) -> Result<String, String> {
+        Ok(self.all()?.iter()
+            .map(|(word, syllable)| format!("{} ({}): {}.", syllable.text, word, syllable.explain()))
+            .collect::<Vec<String>>()
+            .join(" "))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(word: &str) -> Vec<(String, Length, Length)> {
+        syllabify(word).unwrap().into_iter().map(|s| (s.text, s.vowel_length, s.weight)).collect()
+    }
+
+    fn texts(word: &str) -> Vec<String> {
+        syllabify(word).unwrap().into_iter().map(|s| s.text).collect()
+    }
+
+    #[test]
+    fn consonant_clusters_are_split_between_syllables() {
+        assert_eq!(texts("bhavati"), vec!["bha", "va", "ti"]);
+        assert_eq!(texts("attā"), vec!["at", "tā"]);
+        assert_eq!(texts("saṃgha"), vec!["saṃ", "gha"]);
+        assert_eq!(texts("buddhassa"), vec!["bud", "dhas", "sa"]);
+        assert_eq!(texts("amhākaṃ"), vec!["am", "hā", "kaṃ"]);
+    }
+
+    #[test]
+    fn the_intro_lessons_rules() {
+        use Length::*;
+        assert_eq!(split("bhikkhu"), vec![("bhik".to_string(), Short, Long), ("khu".to_string(), Short, Short)]);
+        assert_eq!(split("metta"), vec![("met".to_string(), Short, Long), ("ta".to_string(), Short, Short)]);
+        assert_eq!(split("loko"), vec![("lo".to_string(), Long, Long), ("ko".to_string(), Long, Long)]);
+        assert_eq!(split("ibha")[0], ("i".to_string(), Short, Short)); // bh is one consonant
+        assert_eq!(split("tumhe")[0], ("tum".to_string(), Short, Long)); // mh is two
+        assert_eq!(split("saṃ")[0], ("saṃ".to_string(), Short, Long));
+    }
+
+    #[test]
+    fn decomposed_diacritics_and_capitals_are_read() {
+        assert_eq!(texts("A\u{304}nanda"), vec!["ā", "nan", "da"]);
+    }
+
+    #[test]
+    fn other_letters_are_errors() {
+        assert_eq!(syllabify("bhavatx"), Err("\"x\" in \"bhavatx\" isn't a Pāli letter".to_string()));
+        assert!(syllabify("mm").is_err());
+    }
+
+    #[test]
+    fn every_answer_is_explained() {
+        let syllables = Syllables { words: vec!["attā".to_string(), "ibha".to_string()] };
+        assert_eq!(syllables.table_layout().unwrap().table[1], vec!["attā", "at", "|short|", "|long|"]);
+        assert_eq!(syllables.explanation().unwrap(), "at (attā): a is short, but the double consonant tt after it makes the syllable long. \
+            tā (attā): ā is always long, so the syllable is long. \
+            i (ibha): i is short and bh is one consonant, so the syllable is short. \
+            bha (ibha): a is short and nothing follows it, so the syllable is short.");
+    }
+}
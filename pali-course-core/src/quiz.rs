@@ -0,0 +1,179 @@
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+
+use crate::model::{Course, Exercise, Lesson, TableLayout};
+use crate::seed::seeded_rng;
+use crate::table::{count_header_rows, create_parsed_table, split_bars};
+
+/**
+ * Which rows a quiz is made from. The same config always gives the same quiz.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct QuizConfig {
+    pub from: Option<String>, // lesson path, from the first lesson when None
+    pub to: Option<String>, // lesson path, through the last lesson when None
+    pub categories: Vec<String>, // ids, any exercise when empty
+    pub rows: usize, // for each table
+    pub seed: u64,
+}
+
+/// rows from every table with the same header, as one table
+#[derive(PartialEq, Clone, Debug)]
+pub struct QuizTable {
+    pub table_layout: TableLayout,
+    pub sources: Vec<String>, // ids of the exercises the rows could have come from
+}
+
+/// tables fit together when their header rows say the same thing and they're as wide
+#[derive(PartialEq)]
+struct HeaderKey {
+    header: Vec<Vec<String>>,
+    width: usize,
+}
+
+struct QuizGroup<'a> {
+    key: HeaderKey,
+    layouts: Vec<(&'a Exercise, &'a TableLayout, usize)>, // with its number of header rows
+}
+
+/**
+ * One table for each header among the chosen exercises, each with up to `rows` of their rows.
+ * Rows that are the same in more than one table only count once.
+ */
+pub fn build_quiz(course: &Course, config: &QuizConfig) -> Vec<QuizTable> {
+    let position = |path: &Option<String>| path.as_ref().and_then(|path| course.lessons.iter().position(|l: &Lesson| &l.path == path));
+    let first = position(&config.from).unwrap_or(0);
+    let last = position(&config.to).unwrap_or(course.lessons.len().saturating_sub(1));
+    if course.lessons.is_empty() || first > last {
+        return vec![];
+    }
+
+    let mut groups: Vec<QuizGroup> = Vec::new();
+    for exercise in course.lessons[first..=last].iter().flat_map(|l: &Lesson| &l.exercises) {
+        let in_categories = config.categories.is_empty() || config.categories.iter().any(|id| exercise.categories.iter().flatten().contains(id));
+        let Some(table_layout) = exercise.table_layout.as_ref().filter(|_| in_categories && exercise.generated.is_none()) else { continue };
+        let header_rows = count_header_rows(&create_parsed_table(&table_layout.table));
+        let key = header_key(table_layout, header_rows);
+        match groups.iter_mut().find(|g| g.key == key) {
+            Some(group) => group.layouts.push((exercise, table_layout, header_rows)),
+            None => groups.push(QuizGroup { key, layouts: vec![(exercise, table_layout, header_rows)] }),
+        }
+    }
+
+    let mut rng = seeded_rng(config.seed);
+    groups.iter()
+        .filter_map(|group| {
+            let candidates: Vec<&Vec<String>> = group.layouts.iter()
+                .flat_map(|(_, table_layout, header_rows)| table_layout.table.iter().skip(*header_rows))
+                .filter(|row: &&Vec<String>| row.iter().any(|value| split_bars(value.clone()).is_interactive()))
+                .unique()
+                .collect();
+            if candidates.is_empty() {
+                return None;
+            }
+            let (_, first_layout, header_rows) = group.layouts[0];
+            let mut table = first_layout.table[..header_rows].to_vec();
+            table.extend(candidates.choose_multiple(&mut rng, config.rows).map(|row: &&Vec<String>| (*row).clone()));
+            Some(QuizTable {
+                table_layout: TableLayout {
+                    table,
+                    shuffle_rows: None,
+                    default_mode: None,
+                    ..first_layout.clone()
+                },
+                sources: group.layouts.iter().map(|(exercise, _, _)| exercise.id.clone()).collect(),
+            })
+        })
+        .collect()
+}
+
+fn header_key(table_layout: &TableLayout, header_rows: usize) -> HeaderKey {
+    HeaderKey {
+        header: table_layout.table[..header_rows].iter()
+            .map(|row| row.iter().map(|value| value.trim().to_lowercase()).collect())
+            .collect(),
+        width: table_layout.table.iter().map(|row| row.len()).max().unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Courses;
+
+    fn course() -> Course {
+        let mut courses: Courses = serde_json::from_str(r#"{ "courses": [{
+            "name": "Course",
+            "path": "course",
+            "lessons": [
+                { "name": "Lesson 1", "path": "1", "exercises": [
+                    { "title": "Nouns", "categories": ["vocab"], "table_layout": { "table": [["noun", "meaning"], ["|kāya|", "|body|"], ["|kāla|", "|time|"]], "key_col": 0 } },
+                    { "title": "Present", "categories": ["conjugation"], "table_layout": { "table": [["person", "singular"], ["3rd", "|bhavati|"]] } }
+                ]},
+                { "name": "Lesson 2", "path": "2", "exercises": [
+                    { "title": "More nouns", "categories": ["vocab"], "table_layout": { "table": [["Noun ", "Meaning"], ["|dhamma|", "|teaching|"], ["|kāya|", "|body|"]], "shuffle_rows": true } },
+                    { "title": "Script", "categories": ["vocab"], "generated": { "type": "Script" } }
+                ]},
+                { "name": "Lesson 3", "path": "3", "exercises": [
+                    { "title": "Even more nouns", "categories": ["vocab"], "table_layout": { "table": [["noun", "meaning"], ["|rūpa|", "|form|"]] } }
+                ]}
+            ]
+        }]}"#).unwrap();
+        courses.assign_ids();
+        courses.courses.remove(0)
+    }
+
+    fn config(from: Option<&str>, to: Option<&str>, categories: &[&str], rows: usize) -> QuizConfig {
+        QuizConfig {
+            from: from.map(|s| s.to_string()),
+            to: to.map(|s| s.to_string()),
+            categories: categories.iter().map(|s| s.to_string()).collect(),
+            rows,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn tables_with_the_same_header_are_merged() {
+        let quiz = build_quiz(&course(), &config(None, Some("2"), &["vocab"], 10));
+        assert_eq!(quiz.len(), 1);
+        assert_eq!(quiz[0].sources, vec!["course/1-Nouns", "course/2-More nouns"]);
+        let table = &quiz[0].table_layout.table;
+        assert_eq!(table[0], vec!["noun", "meaning"]);
+        assert_eq!(table.len(), 4); // the repeated kāya row is only in once
+    }
+
+    #[test]
+    fn settings_come_from_the_first_table() {
+        let quiz = build_quiz(&course(), &config(Some("1"), Some("2"), &["vocab"], 10));
+        assert_eq!(quiz[0].table_layout.key_col, Some(0));
+        assert_eq!(quiz[0].table_layout.shuffle_rows, None);
+    }
+
+    #[test]
+    fn rows_are_sampled_from_the_lesson_range() {
+        let quiz = build_quiz(&course(), &config(Some("2"), None, &["vocab"], 2));
+        assert_eq!(quiz[0].sources, vec!["course/2-More nouns", "course/3-Even more nouns"]);
+        assert_eq!(quiz[0].table_layout.table.len(), 3);
+        assert!(quiz[0].table_layout.table[1..].iter().all(|row| row[0] != "|kāla|"));
+    }
+
+    #[test]
+    fn no_categories_is_every_table() {
+        let quiz = build_quiz(&course(), &config(None, None, &[], 10));
+        assert_eq!(quiz.len(), 2);
+        assert_eq!(quiz[1].table_layout.table, vec![vec!["person", "singular"], vec!["3rd", "|bhavati|"]]);
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_quiz() {
+        let course = course();
+        let quiz = build_quiz(&course, &config(None, None, &["vocab"], 2));
+        assert_eq!(build_quiz(&course, &config(None, None, &["vocab"], 2)), quiz);
+    }
+
+    #[test]
+    fn backwards_range_is_empty() {
+        assert_eq!(build_quiz(&course(), &config(Some("3"), Some("1"), &[], 10)), vec![]);
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::TableLayout;
+use crate::table::{create_parsed_table, count_header_rows, Location, ParsedCell};
+
+const NEW_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+
+/// a cell to remember: the exercise's id ("{course path}/{lesson path}-{title}") and where the cell is in its table
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+pub struct CardKey {
+    pub exercise: String,
+    pub location: Location,
+}
+
+/**
+ * SM-2 scheduling for one card. Days are counted from any fixed point, as long as it's always the same one.
+ */
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct Card {
+    pub ease: f64,
+    pub interval: u32, // days until the next review
+    pub repetitions: u32, // remembered in a row
+    pub due: i64, // day
+}
+
+/// what happened when a card came up in a table
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Recall {
+    Forgot, // checked and wrong
+    Revealed, // clicked to see it, so it might not have been known
+    Remembered, // checked and right
+}
+
+impl Recall {
+    /// SM-2's 0 to 5, where below 3 starts the card over
+    pub fn quality(&self) -> u8 {
+        match self {
+            Recall::Forgot => 1,
+            Recall::Revealed => 3,
+            Recall::Remembered => 5,
+        }
+    }
+}
+
+impl Card {
+
+    pub fn new(today: i64) -> Self {
+        Self { ease: NEW_EASE, interval: 0, repetitions: 0, due: today }
+    }
+
+    pub fn is_due(&self, today: i64) -> bool {
+        self.due <= today
+    }
+
+    /**
+     * Remembering a card before it's due doesn't count, so revealing it again or re-checking the same table doesn't push it further out.
+     * Forgetting always counts.
+     */
+    pub fn review(&mut self, recall: Recall, today: i64) {
+        let quality = recall.quality();
+        if quality >= 3 && !self.is_due(today) {
+            return;
+        }
+        if quality < 3 {
+            self.repetitions = 0;
+            self.interval = 1;
+        } else {
+            self.repetitions += 1;
+            self.interval = match self.repetitions {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval as f64 * self.ease).round() as u32,
+            };
+        }
+        let miss = (5 - quality) as f64;
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+        self.due = today + self.interval as i64;
+    }
+
+}
+
+/// every card that's come up, stored as a list since json keys have to be strings
+#[derive(Serialize, Deserialize, PartialEq, Clone, Default, Debug)]
+#[serde(from = "Vec<(CardKey, Card)>", into = "Vec<(CardKey, Card)>")]
+pub struct ReviewQueue {
+    cards: HashMap<CardKey, Card>,
+}
+
+impl From<Vec<(CardKey, Card)>> for ReviewQueue {
+    fn from(cards: Vec<(CardKey, Card)>) -> Self {
+        Self { cards: cards.into_iter().collect() }
+    }
+}
+
+impl From<ReviewQueue> for Vec<(CardKey, Card)> {
+    fn from(queue: ReviewQueue) -> Self {
+        queue.cards.into_iter().collect()
+    }
+}
+
+impl ReviewQueue {
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn get(&self, key: &CardKey) -> Option<&Card> {
+        self.cards.get(key)
+    }
+
+    /// a card seen for the first time starts out due
+    pub fn record(&mut self, key: CardKey, recall: Recall, today: i64) {
+        self.cards.entry(key).or_insert_with(|| Card::new(today)).review(recall, today);
+    }
+
+    /// the most overdue first
+    pub fn due(&self, today: i64) -> Vec<&CardKey> {
+        let mut due: Vec<(&CardKey, &Card)> = self.cards.iter()
+            .filter(|(_, card)| card.is_due(today))
+            .collect();
+        due.sort_by(|(a_key, a), (b_key, b)| a.due.cmp(&b.due)
+            .then_with(|| a_key.exercise.cmp(&b_key.exercise))
+            .then_with(|| a_key.location.cmp(&b_key.location)));
+        due.into_iter().map(|(key, _)| key).collect()
+    }
+
+}
+
+/**
+ * Where a review table's rows came from, so what's answered in it counts for the cells of the original table
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct ReviewRows {
+    pub exercise: String,
+    pub rows: Vec<usize>, // the original row of each of the review table's rows
+}
+
+impl ReviewRows {
+    /// for a table that's shown whole
+    pub fn all(exercise: &str, row_count: usize) -> Self {
+        Self { exercise: exercise.to_string(), rows: (0..row_count).collect() }
+    }
+
+    pub fn card(&self, location: &Location) -> Option<CardKey> {
+        self.rows.get(location.0).map(|row: &usize| CardKey { exercise: self.exercise.clone(), location: (*row, location.1) })
+    }
+}
+
+/**
+ * The header rows and each row with a due cell, where only the due cells are left to answer.
+ * None when none of the cells are in the table anymore, like after a lesson edit.
+ */
+pub fn review_table(table_layout: &TableLayout, exercise: &str, due: &[Location]) -> Option<(TableLayout, ReviewRows)> {
+    let parsed_table = create_parsed_table(&table_layout.table);
+    let is_due = |location: &Location| due.contains(location)
+        && parsed_table.get(location.0).and_then(|row| row.get(location.1)).is_some_and(|cell| cell.is_interactive());
+    let header_rows = count_header_rows(&parsed_table);
+
+    let rows: Vec<usize> = (0..parsed_table.len())
+        .filter(|row| *row < header_rows || (0..parsed_table[*row].len()).any(|col| is_due(&(*row, col))))
+        .collect();
+    if rows.len() == header_rows {
+        return None;
+    }
+
+    let table = rows.iter().map(|row| table_layout.table[*row].iter().enumerate()
+        .map(|(col, value)| match &parsed_table[*row][col] {
+            ParsedCell::Interactive(text) if !is_due(&(*row, col)) => format!("{}{}{}", text.start, text.middle, text.end),
+            _ => value.clone(),
+        })
+        .collect())
+        .collect();
+
+    let layout = TableLayout {
+        table,
+        shuffle_rows: None,
+        ..table_layout.clone()
+    };
+    Some((layout, ReviewRows { exercise: exercise.to_string(), rows }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(row: usize, col: usize) -> CardKey {
+        CardKey { exercise: "course/1-Verbs".to_string(), location: (row, col) }
+    }
+
+    fn layout(rows: &[&[&str]]) -> TableLayout {
+        TableLayout::new(rows.iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect())
+    }
+
+    #[test]
+    fn remembering_spaces_out_reviews() {
+        let mut card = Card::new(0);
+        card.review(Recall::Remembered, 0);
+        assert_eq!((card.interval, card.due), (1, 1));
+        card.review(Recall::Remembered, 1);
+        assert_eq!((card.interval, card.due), (6, 7));
+        card.review(Recall::Remembered, 7);
+        assert_eq!((card.repetitions, card.interval), (3, 16)); // 6 days at an ease of 2.7
+    }
+
+    #[test]
+    fn forgetting_starts_over_and_lowers_ease() {
+        let mut card = Card::new(0);
+        card.review(Recall::Remembered, 0);
+        card.review(Recall::Remembered, 1);
+        card.review(Recall::Forgot, 3);
+        assert_eq!((card.repetitions, card.interval, card.due), (0, 1, 4));
+        assert!(card.ease < NEW_EASE);
+    }
+
+    #[test]
+    fn ease_has_a_floor() {
+        let mut card = Card::new(0);
+        for day in 0..20 {
+            card.review(Recall::Forgot, day);
+        }
+        assert_eq!(card.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn revealing_is_a_hard_pass() {
+        let mut card = Card::new(0);
+        card.review(Recall::Revealed, 0);
+        assert_eq!((card.repetitions, card.due), (1, 1));
+        assert!(card.ease < NEW_EASE);
+    }
+
+    #[test]
+    fn remembering_early_doesnt_count() {
+        let mut card = Card::new(0);
+        card.review(Recall::Revealed, 0);
+        let before = card.clone();
+        card.review(Recall::Revealed, 0);
+        card.review(Recall::Remembered, 0);
+        assert_eq!(card, before);
+    }
+
+    #[test]
+    fn queue_lists_due_cards_most_overdue_first() {
+        let mut queue = ReviewQueue::default();
+        queue.record(key(1, 1), Recall::Remembered, 0);
+        queue.record(key(2, 1), Recall::Forgot, 2);
+        queue.record(key(3, 1), Recall::Remembered, 2);
+        assert_eq!(queue.due(0), Vec::<&CardKey>::new());
+        assert_eq!(queue.due(3), vec![&key(1, 1), &key(2, 1), &key(3, 1)]);
+        assert_eq!(queue.due(1), vec![&key(1, 1)]);
+    }
+
+    #[test]
+    fn queue_round_trips_through_json() {
+        let mut queue = ReviewQueue::default();
+        queue.record(key(1, 1), Recall::Revealed, 5);
+        let json = serde_json::to_string(&queue).unwrap();
+        assert_eq!(serde_json::from_str::<ReviewQueue>(json.as_str()).unwrap(), queue);
+    }
+
+    #[test]
+    fn review_table_keeps_headers_and_rows_with_due_cells() {
+        let table_layout = layout(&[&["", "sg."], &["1st", "|bhavāmi|"], &["2nd", "|bhavasi|"], &["3rd", "|bhav|ati"]]);
+        let (review, rows) = review_table(&table_layout, "course/1-Verbs", &[(3, 1)]).unwrap();
+        assert_eq!(review, layout(&[&["", "sg."], &["3rd", "|bhav|ati"]]));
+        assert_eq!(rows.rows, vec![0, 3]);
+        assert_eq!(rows.card(&(1, 1)), Some(CardKey { exercise: "course/1-Verbs".to_string(), location: (3, 1) }));
+    }
+
+    #[test]
+    fn review_table_shows_cells_that_arent_due() {
+        let table_layout = layout(&[&["Eng", "Pāli"], &["|body|", "|kāya|"]]);
+        let (review, _) = review_table(&table_layout, "course/1-Vocab", &[(1, 1)]).unwrap();
+        assert_eq!(review.table[1], vec!["body", "|kāya|"]);
+    }
+
+    #[test]
+    fn review_table_without_due_cells_is_none() {
+        let table_layout = layout(&[&["Eng", "Pāli"], &["|body|", "kāya"]]);
+        assert_eq!(review_table(&table_layout, "course/1-Vocab", &[(1, 1), (5, 0)]), None);
+    }
+}
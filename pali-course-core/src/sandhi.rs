@@ -0,0 +1,286 @@
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::check::AnswerCheck;
+use crate::model::{ExerciseMode, TableLayout};
+use crate::prosody::{syllabify, Length};
+use crate::table::DataTable;
+
+/**
+ * The "combine A + B" exercise: each pair of words or morphemes is to be written joined, and any combination the
+ * rules allow is right.
+ */
+#[derive(PartialEq, Clone, Deserialize, Debug)]
+pub struct Sandhi {
+    pub pairs: Vec<[String; 2]>, // in IAST
+}
+
+/// letters a rule matches, with how the chart shows them
+pub struct Letters {
+    pub name: &'static str,
+    pub letters: &'static [&'static str],
+    pub whole_word: bool, // only matches a word that is just the letters, so iti but not itivuttaka
+}
+
+/**
+ * Where a word ending in one of the ends meets one starting with one of the starts, the two letters become the
+ * joined text, in which 1 is the end and 2 the start as they were.
+ */
+pub struct Rule {
+    pub description: &'static str,
+    pub ends: Letters,
+    pub starts: Letters,
+    pub joined: &'static str,
+    pub example: [&'static str; 3], // first, second, combined
+}
+
+const fn letters(name: &'static str, letters: &'static [&'static str]) -> Letters {
+    Letters { name, letters, whole_word: false }
+}
+
+const VOWELS: Letters = letters("any vowel", &["a", "ā", "i", "ī", "u", "ū", "e", "o"]);
+// aspirates start with these too, so they're matched by them
+const CONSONANTS: Letters = letters("any consonant", &["k", "g", "ṅ", "c", "j", "ñ", "ṭ", "ḍ", "ṇ", "t", "d", "n", "p", "b", "m", "y", "r", "l", "ḷ", "v", "s", "h"]);
+const A: Letters = letters("a ā", &["a", "ā"]);
+const I: Letters = letters("i ī", &["i", "ī"]);
+const U: Letters = letters("u ū", &["u", "ū"]);
+const NIGGAHITA: Letters = letters("ṃ", &["ṃ"]);
+const ITI: Letters = Letters { whole_word: true, ..letters("iti", &["iti"]) };
+
+/// for iti, used instead of the general rules when one of them fits
+pub const WORD_RULES: [Rule; 5] = [
+    Rule { description: "the vowel before iti is long and its i is dropped", ends: A, starts: ITI, joined: "āti", example: ["kusalā", "iti", "kusalāti"] },
+    Rule { description: "the vowel before iti is long and its i is dropped", ends: I, starts: ITI, joined: "īti", example: ["atthi", "iti", "atthīti"] },
+    Rule { description: "the vowel before iti is long and its i is dropped", ends: U, starts: ITI, joined: "ūti", example: ["sādhu", "iti", "sādhūti"] },
+    Rule { description: "the i of iti is dropped after e and o", ends: letters("e o", &["e", "o"]), starts: ITI, joined: "1ti", example: ["so", "iti", "soti"] },
+    Rule { description: "ṃ and iti make nti", ends: NIGGAHITA, starts: ITI, joined: "nti", example: ["kātuṃ", "iti", "kātunti"] },
+];
+
+/**
+ * In order of how usual they are, so a pair's first combination is the one the answer shows. None of them make a
+ * long vowel before a double consonant (see combinations).
+ */
+pub const RULES: [Rule; 19] = [
+    Rule { description: "like vowels make a long one", ends: A, starts: A, joined: "ā", example: ["tatra", "ayaṃ", "tatrāyaṃ"] },
+    Rule { description: "like vowels make a long one", ends: I, starts: I, joined: "ī", example: ["tīṇi", "imāni", "tīṇīmāni"] },
+    Rule { description: "like vowels make a long one", ends: U, starts: U, joined: "ū", example: ["bahu", "upakāra", "bahūpakāra"] },
+    Rule { description: "a and i make e", ends: A, starts: I, joined: "e", example: ["upa", "ikkhati", "upekkhati"] },
+    Rule { description: "a and u make o", ends: A, starts: U, joined: "o", example: ["na", "upeti", "nopeti"] },
+    Rule { description: "i or e becomes y before another vowel", ends: letters("i ī e", &["i", "ī", "e"]), starts: letters("a ā u ū o", &["a", "ā", "u", "ū", "o"]), joined: "y2", example: ["vi", "ākaroti", "vyākaroti"] },
+    Rule { description: "u or o becomes v before another vowel", ends: letters("u ū o", &["u", "ū", "o"]), starts: letters("a ā i ī e", &["a", "ā", "i", "ī", "e"]), joined: "v2", example: ["su", "āgataṃ", "svāgataṃ"] },
+    // not a before i or u, which make e and o
+    Rule { description: "the first vowel is dropped", ends: A, starts: letters("a ā e o", &["a", "ā", "e", "o"]), joined: "2", example: ["na", "atthi", "natthi"] },
+    Rule { description: "the first vowel is dropped", ends: letters("i ī u ū e o", &["i", "ī", "u", "ū", "e", "o"]), starts: VOWELS, joined: "2", example: ["hi", "etaṃ", "hetaṃ"] },
+    Rule { description: "nothing changes before a consonant", ends: VOWELS, starts: CONSONANTS, joined: "12", example: ["bhikkhu", "saṃgha", "bhikkhusaṃgha"] },
+    Rule { description: "ṃ becomes m before a vowel", ends: NIGGAHITA, starts: VOWELS, joined: "m2", example: ["taṃ", "ahaṃ", "tamahaṃ"] },
+    Rule { description: "ṃ and e make ññe", ends: NIGGAHITA, starts: letters("e", &["e"]), joined: "ññ2", example: ["taṃ", "eva", "taññeva"] },
+    Rule { description: "ṃ stays before a consonant", ends: NIGGAHITA, starts: CONSONANTS, joined: "12", example: ["saṃ", "gaha", "saṃgaha"] },
+    Rule { description: "ṃ becomes the nasal of the consonant after it", ends: NIGGAHITA, starts: letters("k g", &["k", "g"]), joined: "ṅ2", example: ["saṃ", "gaha", "saṅgaha"] },
+    Rule { description: "ṃ becomes the nasal of the consonant after it", ends: NIGGAHITA, starts: letters("c j", &["c", "j"]), joined: "ñ2", example: ["saṃ", "jāta", "sañjāta"] },
+    Rule { description: "ṃ becomes the nasal of the consonant after it", ends: NIGGAHITA, starts: letters("ṭ ḍ", &["ṭ", "ḍ"]), joined: "ṇ2", example: ["saṃ", "ṭhāna", "saṇṭhāna"] },
+    Rule { description: "ṃ becomes the nasal of the consonant after it", ends: NIGGAHITA, starts: letters("t d n", &["t", "d", "n"]), joined: "n2", example: ["saṃ", "tosa", "santosa"] },
+    Rule { description: "ṃ becomes the nasal of the consonant after it", ends: NIGGAHITA, starts: letters("p b m", &["p", "b", "m"]), joined: "m2", example: ["saṃ", "buddha", "sambuddha"] },
+    Rule { description: "ṃ and y make ññ", ends: NIGGAHITA, starts: letters("y", &["y"]), joined: "ññ", example: ["saṃ", "yoga", "saññoga"] },
+];
+
+fn all_rules() -> impl Iterator<Item = &'static Rule> {
+    WORD_RULES.iter().chain(RULES.iter())
+}
+
+/// " Saṁ" -> "saṃ"
+fn normalize(word: &str) -> String {
+    word.trim().to_lowercase().nfc().collect::<String>().replace(['ṁ', 'ŋ'], "ṃ")
+}
+
+impl Rule {
+
+    /// the two combined by this rule, if it's one for where they meet
+    pub fn apply(&self, first: &str, second: &str) -> Option<String> {
+        let end = self.ends.letters.iter().find(|end| first.ends_with(*end))?;
+        let start = self.starts.letters.iter().find(|start| if self.starts.whole_word { second == **start } else { second.starts_with(*start) })?;
+        let joined = self.joined.replace('1', end).replace('2', start);
+        Some(format!("{}{}{}", &first[..first.len() - end.len()], joined, &second[start.len()..]))
+    }
+
+}
+
+fn check_word(word: &str) -> Result<(), String> {
+    syllabify(word)?;
+    if !VOWELS.letters.iter().chain(NIGGAHITA.letters).any(|end| word.ends_with(end)) {
+        return Err(format!("\"{}\" doesn't end in a vowel or ṃ", word));
+    }
+    Ok(())
+}
+
+/// "nātthi" has one; vowels are short there, though a few words are written with one
+fn long_before_double(word: &str) -> usize {
+    syllabify(word).map(|syllables| syllables.iter().filter(|s| s.vowel_length == Length::Long && s.closed()).count()).unwrap_or(0)
+}
+
+/**
+ * Every way the two can be combined, with the rule for each, the most usual first. The word rules are used instead
+ * of the general ones when they fit, and combinations that make a long vowel before a double consonant are left out.
+ */
+pub fn combinations(first: &str, second: &str) -> Result<Vec<(&'static Rule, String)>, String> {
+    let (first, second) = (normalize(first), normalize(second));
+    check_word(first.as_str())?;
+    syllabify(second.as_str())?;
+    let rules = if WORD_RULES.iter().any(|rule| rule.apply(first.as_str(), second.as_str()).is_some()) { &WORD_RULES[..] } else { &RULES[..] };
+    let long_before_double_already = long_before_double(first.as_str()) + long_before_double(second.as_str());
+    let mut combinations: Vec<(&'static Rule, String)> = vec![];
+    for rule in rules {
+        let Some(combined) = rule.apply(first.as_str(), second.as_str()) else { continue };
+        if long_before_double(combined.as_str()) <= long_before_double_already && !combinations.iter().any(|(_, c)| *c == combined) {
+            combinations.push((rule, combined));
+        }
+    }
+    Ok(combinations)
+}
+
+/// "su" + "āgataṃ" -> ["svāgataṃ", "sāgataṃ"]
+pub fn combine(first: &str, second: &str) -> Result<Vec<String>, String> {
+    Ok(combinations(first, second)?.into_iter().map(|(_, combined)| combined).collect())
+}
+
+/**
+ * The pairs of words the combined form could be made from, "natthi" -> [("na", "atthi"), ("nā", "atthi"), ...].
+ * Any vowel could have been dropped, so there are usually a lot of them.
+ */
+pub fn split(combined: &str) -> Vec<(String, String)> {
+    let combined = normalize(combined);
+    let mut candidates: Vec<(String, String)> = vec![];
+    for rule in all_rules() {
+        for end in rule.ends.letters {
+            for start in rule.starts.letters {
+                let joined = rule.joined.replace('1', end).replace('2', start);
+                for (at, _) in combined.match_indices(joined.as_str()).filter(|(at, _)| *at > 0) {
+                    let first = format!("{}{}", &combined[..at], end);
+                    let second = format!("{}{}", start, &combined[at + joined.len()..]);
+                    let made = combine(first.as_str(), second.as_str()).is_ok_and(|made| made.contains(&combined));
+                    if made && !candidates.contains(&(first.clone(), second.clone())) {
+                        candidates.push((first, second));
+                    }
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// the rules as a table, with an example of each
+pub fn chart() -> DataTable {
+    let mut table: DataTable = vec![vec!["the first ends in".to_string(), "the second starts with".to_string(), "rule".to_string(), "for example".to_string()]];
+    for rule in all_rules() {
+        let [first, second, combined] = rule.example;
+        table.push(vec![rule.ends.name.to_string(), rule.starts.name.to_string(), rule.description.to_string(), format!("{} + {} = {}", first, second, combined)]);
+    }
+    table
+}
+
+impl Sandhi {
+
+    /// a row for each pair, to be typed in combined
+    pub fn table_layout(&self) -> Result<TableLayout, String> {
+        let mut table: DataTable = vec![vec!["first".to_string(), "second".to_string(), "combined".to_string()]];
+        for [first, second] in &self.pairs {
+            let combined = combine(first, second)?.into_iter().next().unwrap_or_default();
+            table.push(vec![first.clone(), second.clone(), format!("|{}|", combined)]);
+        }
+        Ok(TableLayout {
+            pali_cols: Some(vec![0, 1, 2]),
+            default_mode: Some(ExerciseMode::TypeField),
+            answer_check: Some(AnswerCheck::Sandhi),
+            ..TableLayout::new(table)
+        })
+    }
+
+    /// which rule makes each pair's combination
+    pub fn explanation(&self) -> Result<String, String> {
+        let mut sentences = vec![];
+        for [first, second] in &self.pairs {
+            if let Some((rule, combined)) = combinations(first, second)?.into_iter().next() {
+                sentences.push(format!("{} + {} = {}: {}.", first, second, combined, rule.description));
+            }
+        }
+        Ok(sentences.join(" "))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(strings: &[&str]) -> Vec<String> {
+        strings.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn every_rule_makes_its_example() {
+        for rule in all_rules() {
+            let [first, second, combined] = rule.example;
+            assert_eq!(rule.apply(first, second).as_deref(), Some(combined), "{}", rule.description);
+            assert!(combine(first, second).unwrap().contains(&combined.to_string()), "{}", rule.description);
+        }
+    }
+
+    #[test]
+    fn vowels_have_more_than_one_combination() {
+        assert_eq!(combine("tatra", "ayaṃ").unwrap(), strings(&["tatrāyaṃ", "tatrayaṃ"]));
+        assert_eq!(combine("su", "āgataṃ").unwrap(), strings(&["svāgataṃ", "sāgataṃ"]));
+    }
+
+    #[test]
+    fn long_vowels_arent_made_before_double_consonants() {
+        assert_eq!(combine("na", "atthi").unwrap(), strings(&["natthi"]));
+        assert_eq!(combine("upa", "ikkhati").unwrap(), strings(&["upekkhati"]));
+    }
+
+    #[test]
+    fn iti_has_its_own_rules() {
+        assert_eq!(combine("atthi", "iti").unwrap(), strings(&["atthīti"]));
+        assert_eq!(combine("kusalā", "iti").unwrap(), strings(&["kusalāti"]));
+        assert_eq!(combine("kātuṃ", "iti").unwrap(), strings(&["kātunti"]));
+        assert!(!combine("dhammo", "itivuttaka").unwrap().contains(&"dhammotivuttaka".to_string()));
+    }
+
+    #[test]
+    fn niggahita_before_consonants_can_stay() {
+        assert_eq!(combine("saṃ", "gaha").unwrap(), strings(&["saṃgaha", "saṅgaha"]));
+        assert_eq!(combine("Saṁ", "buddha").unwrap(), strings(&["saṃbuddha", "sambuddha"]));
+        assert_eq!(combine("bhikkhu", "saṃgha").unwrap(), strings(&["bhikkhusaṃgha"]));
+    }
+
+    #[test]
+    fn words_have_to_be_pali() {
+        assert_eq!(combine("bhavat", "iti"), Err("\"bhavat\" doesn't end in a vowel or ṃ".to_string()));
+        assert!(combine("na", "xyz").is_err());
+    }
+
+    #[test]
+    fn splits_include_the_words_it_was_made_from() {
+        let candidates = split("natthi");
+        assert!(candidates.contains(&("na".to_string(), "atthi".to_string())));
+        assert!(split("saññoga").contains(&("saṃ".to_string(), "yoga".to_string())));
+        assert!(split("kātunti").contains(&("kātuṃ".to_string(), "iti".to_string())));
+        for (first, second) in candidates {
+            assert!(combine(first.as_str(), second.as_str()).unwrap().contains(&"natthi".to_string()));
+        }
+    }
+
+    #[test]
+    fn chart_has_a_row_for_each_rule() {
+        let chart = chart();
+        assert_eq!(chart.len(), WORD_RULES.len() + RULES.len() + 1);
+        assert_eq!(chart[1], vec!["a ā", "iti", "the vowel before iti is long and its i is dropped", "kusalā + iti = kusalāti"]);
+    }
+
+    #[test]
+    fn exercise_shows_the_usual_combination_and_explains_it() {
+        let sandhi = Sandhi { pairs: vec![["taṃ".to_string(), "ahaṃ".to_string()]] };
+        let layout = sandhi.table_layout().unwrap();
+        assert_eq!(layout.table[1], vec!["taṃ", "ahaṃ", "|tamahaṃ|"]);
+        assert_eq!(layout.answer_check, Some(AnswerCheck::Sandhi));
+        assert_eq!(sandhi.explanation().unwrap(), "taṃ + ahaṃ = tamahaṃ: ṃ becomes m before a vowel.");
+    }
+}
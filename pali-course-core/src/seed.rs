@@ -0,0 +1,40 @@
+//! Orders made from a seed, which have to come out the same wherever a ?seed= link is opened.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// ChaCha8 gives the same numbers in every build, where StdRng can change with a rand release
+pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// 64 bit FNV-1a; DefaultHasher can hash differently after a Rust release
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// each table on a page gets its own order from the page's seed
+pub fn table_seed(seed: u32, id: &str) -> u64 {
+    fnv1a(&[&seed.to_le_bytes()[..], id.as_bytes()].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+
+    use super::*;
+
+    #[test]
+    fn fnv1a_matches_the_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn a_seed_always_gives_the_same_order() {
+        let mut rows: Vec<usize> = (0..8).collect();
+        rows.shuffle(&mut seeded_rng(table_seed(1234, "warder/1-Nouns")));
+        assert_eq!(rows, vec![4, 0, 2, 3, 6, 7, 5, 1]);
+    }
+}
@@ -0,0 +1,259 @@
+use itertools::Itertools;
+
+pub type DataTable = Vec<Vec<String>>;
+pub type ParsedTable = Vec<Vec<ParsedCell>>;
+pub type Location = (usize, usize);
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum ParsedCell {
+    Label(String),
+    Interactive(TriSplit),
+}
+
+impl ParsedCell {
+    pub fn is_interactive(&self) -> bool {
+        matches!(self, ParsedCell::Interactive(_))
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct TriSplit {
+    pub start: String,
+    pub middle: String, // as displayed
+    pub end: String,
+    pub answers: Vec<String>, // accepted for the middle
+}
+
+impl TriSplit {
+    pub fn new(start: String, middle: String, end: String) -> Self {
+        let (middle, answers) = split_alternatives(middle.as_str());
+        Self { start, middle, end, answers }
+    }
+}
+
+/**
+ * "says, speaks" -> ("says, speaks", ["says, speaks", "says", "speaks"])
+ * "/" and "," separate alternatives; "\/" and "\," are kept as they are ("goes away\/from" -> ["goes away/from"])
+ */
+fn split_alternatives(middle: &str) -> (String, Vec<String>) {
+    let mut display = String::new();
+    let mut alternatives: Vec<String> = vec![String::new()];
+    let mut chars = middle.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek().is_some_and(|next| *next == '/' || *next == ',') => {
+                let escaped = chars.next().unwrap();
+                display.push(escaped);
+                alternatives.last_mut().unwrap().push(escaped);
+            },
+            '/' | ',' => {
+                display.push(c);
+                alternatives.push(String::new());
+            },
+            _ => {
+                display.push(c);
+                alternatives.last_mut().unwrap().push(c);
+            },
+        }
+    }
+
+    let mut answers = vec![display.clone()];
+    if alternatives.len() > 1 {
+        answers.extend(alternatives.iter()
+            .map(|a: &String| a.trim().to_string())
+            .filter(|a: &String| !a.is_empty()));
+    }
+    (display, answers.into_iter().unique().collect())
+}
+
+/// "pi |su|kkha" -> start "pi ", answer "su", end "kkha". Without a pair of bars, it's a label
+pub fn split_bars(str: String) -> ParsedCell {
+    if let Some(left) = str.find('|') {
+        let (start, middle) = str.split_at(left);
+        let (_, middle) = middle.split_at(1);
+        if let Some(right) = middle.find('|') {
+            let (middle, end) = middle.split_at(right);
+            let (_, end) = end.split_at(1);
+            return ParsedCell::Interactive(TriSplit::new(start.to_string(), middle.to_string(), end.to_string()));
+        }
+    }
+    ParsedCell::Label(str)
+}
+
+pub fn create_parsed_table(table: &DataTable) -> ParsedTable {
+    table.clone().iter()
+        .map(|row: &Vec<String>| row.iter()
+            .map(|val: &String| split_bars(val.clone()))
+            .collect::<Vec<ParsedCell>>())
+        .collect()
+}
+
+pub fn create_location_table(table: &DataTable) -> Vec<Vec<Location>> {
+    (0..table.len()).map(|row_index|
+        (0..table.get(row_index)
+            .map(|row: &Vec<String>| row.len())
+            .unwrap_or(0))
+        .map(|col_index| (row_index, col_index))
+        .collect())
+    .collect()
+}
+
+/// leading rows without anything to answer, such as ["person", "singular", "plural"]
+pub fn count_header_rows(parsed_table: &ParsedTable) -> usize {
+    parsed_table.iter()
+        .take_while(|row: &&Vec<ParsedCell>| !row.iter().any(|c| c.is_interactive()))
+        .count()
+}
+
+/**
+ * The table quizzed from another column: the prompt column's cells are shown whole,
+ * and every other cell below the header rows is to be answered, even ones written without |bars|.
+ */
+pub fn prompt_with_column(parsed_table: &ParsedTable, prompt_col: usize) -> ParsedTable {
+    let header_rows = count_header_rows(parsed_table);
+    parsed_table.iter().enumerate()
+        .map(|(row, cells)| cells.iter().enumerate()
+            .map(|(col, cell)| match cell {
+                _ if row < header_rows => cell.clone(),
+                ParsedCell::Interactive(text) if col == prompt_col => ParsedCell::Label(format!("{}{}{}", text.start, text.middle, text.end)),
+                ParsedCell::Label(value) if col != prompt_col && !value.trim().is_empty() =>
+                    ParsedCell::Interactive(TriSplit::new(String::new(), value.clone(), String::new())),
+                _ => cell.clone(),
+            })
+            .collect())
+        .collect()
+}
+
+pub trait DirectionalInclusive<T> {
+    fn left (&self, of: T) -> bool;
+    fn right(&self, of: T) -> bool;
+    fn above(&self, of: T) -> bool;
+    fn below(&self, of: T) -> bool;
+}
+
+impl DirectionalInclusive<&Location> for Location {
+    fn left (&self, of: &Location) -> bool {
+        self.1 < of.1
+    }
+    fn right(&self, of: &Location) -> bool {
+        self.1 > of.1
+    }
+    fn above(&self, of: &Location) -> bool {
+        self.0 < of.0
+    }
+    fn below(&self, of: &Location) -> bool {
+        self.0 > of.0
+    }
+}
+
+pub trait GetLocation<T> {
+    fn get_location(&self, location: &Location) -> Option<&T>;
+    fn get_location_unchecked(&self, location: &Location) -> &T;
+}
+
+impl<T> GetLocation<T> for Vec<Vec<T>> {
+    fn get_location(&self, location: &Location) -> Option<&T> {
+        self.get(location.0).and_then(|v| v.get(location.1))
+    }
+
+    fn get_location_unchecked(&self, location: &Location) -> &T {
+        self.get(location.0).unwrap().get(location.1).unwrap()
+    }
+}
+
+/// a table from string literals, for tests
+#[cfg(test)]
+pub(crate) fn table(rows: &[&[&str]]) -> DataTable {
+    rows.iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interactive(start: &str, middle: &str, end: &str) -> ParsedCell {
+        ParsedCell::Interactive(TriSplit::new(start.to_string(), middle.to_string(), end.to_string()))
+    }
+
+    #[test]
+    fn split_bars_finds_the_answer_inside_a_cell() {
+        assert_eq!(split_bars("pi |su|kkha".to_string()), interactive("pi ", "su", "kkha"));
+        assert_eq!(split_bars("|kāya|".to_string()), interactive("", "kāya", ""));
+    }
+
+    #[test]
+    fn split_bars_without_a_pair_is_a_label() {
+        assert_eq!(split_bars("person".to_string()), ParsedCell::Label("person".to_string()));
+        assert_eq!(split_bars("|unclosed".to_string()), ParsedCell::Label("|unclosed".to_string()));
+    }
+
+    #[test]
+    fn single_answer_has_no_alternatives() {
+        let split = TriSplit::new(String::new(), "kāya".to_string(), String::new());
+        assert_eq!(split.middle, "kāya");
+        assert_eq!(split.answers, vec!["kāya"]);
+    }
+
+    #[test]
+    fn alternatives_are_each_accepted() {
+        let split = TriSplit::new(String::new(), "says, speaks".to_string(), String::new());
+        assert_eq!(split.middle, "says, speaks");
+        assert_eq!(split.answers, vec!["says, speaks", "says", "speaks"]);
+
+        let split = TriSplit::new(String::new(), "asmi/amhi".to_string(), String::new());
+        assert_eq!(split.answers, vec!["asmi/amhi", "asmi", "amhi"]);
+    }
+
+    #[test]
+    fn escaped_separators_stay_in_one_answer() {
+        let split = TriSplit::new(String::new(), "goes away\\/from".to_string(), String::new());
+        assert_eq!(split.middle, "goes away/from");
+        assert_eq!(split.answers, vec!["goes away/from"]);
+    }
+
+    #[test]
+    fn empty_alternatives_are_dropped() {
+        let split = TriSplit::new(String::new(), "a,,b/".to_string(), String::new());
+        assert_eq!(split.answers, vec!["a,,b/", "a", "b"]);
+    }
+
+    #[test]
+    fn locations_follow_ragged_rows() {
+        let locations = create_location_table(&table(&[&["a", "b"], &["c"]]));
+        assert_eq!(locations, vec![vec![(0, 0), (0, 1)], vec![(1, 0)]]);
+    }
+
+    #[test]
+    fn header_rows_are_the_leading_rows_without_answers() {
+        let parsed = create_parsed_table(&table(&[&["", "sg."], &["1st", "pl."], &["1st", "|bhavāmi|"], &["2nd", "bhavasi"]]));
+        assert_eq!(count_header_rows(&parsed), 2);
+        assert_eq!(count_header_rows(&create_parsed_table(&table(&[&["a"]]))), 1);
+    }
+
+    #[test]
+    fn prompting_with_a_column_shows_it_and_asks_for_the_rest() {
+        let parsed = create_parsed_table(&table(&[&["he is", "meaning"], &["ahosi", "|there is|"], &["bhav|ati|", ""]]));
+        assert_eq!(prompt_with_column(&parsed, 1), vec![
+            vec![ParsedCell::Label("he is".to_string()), ParsedCell::Label("meaning".to_string())],
+            vec![interactive("", "ahosi", ""), ParsedCell::Label("there is".to_string())],
+            vec![interactive("bhav", "ati", ""), ParsedCell::Label(String::new())],
+        ]);
+    }
+
+    #[test]
+    fn get_location_is_none_outside_the_table() {
+        let parsed = create_parsed_table(&table(&[&["a", "|b|"]]));
+        assert!(parsed.get_location(&(0, 1)).unwrap().is_interactive());
+        assert_eq!(parsed.get_location(&(1, 0)), None);
+        assert_eq!(parsed.get_location(&(0, 2)), None);
+    }
+
+    #[test]
+    fn directions_are_strict() {
+        let location: Location = (1, 1);
+        assert!((1, 0).left(&location) && !(1, 1).left(&location));
+        assert!((1, 2).right(&location));
+        assert!((0, 1).above(&location) && !(1, 1).above(&location));
+        assert!((2, 1).below(&location));
+    }
+}
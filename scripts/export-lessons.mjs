@@ -0,0 +1,20 @@
+// Writes the lessons bundled in src/main.js to static/lessons/ as the fetched json files.
+// usage: node scripts/export-lessons.mjs
+
+import { readFile, writeFile, mkdir } from 'node:fs/promises';
+
+const source = await readFile(new URL('../src/main.js', import.meta.url), 'utf8');
+const { get_lessons_json } = await import('data:text/javascript,' + encodeURIComponent(source));
+const { courses } = get_lessons_json();
+
+const out = new URL('../static/lessons/', import.meta.url);
+for (const course of courses) {
+    const courseOut = new URL(`${course.path}/`, out);
+    await mkdir(courseOut, { recursive: true });
+    for (const lesson of course.lessons) {
+        await writeFile(new URL(`${lesson.path}.json`, courseOut), JSON.stringify(lesson, null, 2) + '\n');
+    }
+}
+const index = { courses: courses.map(course => ({ ...course, lessons: course.lessons.map(l => l.path) })) };
+await writeFile(new URL('index.json', out), JSON.stringify(index, null, 2) + '\n');
+console.log(`exported ${courses.length} courses`);
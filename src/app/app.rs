@@ -7,9 +7,12 @@ use stylist::yew::{Global, styled_component};
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use crate::{get_lessons_json, log_dbg, log_display, log_str};
-use crate::contexts::{Exercise, ExerciseCategory, ExerciseComponent, Exercises, Lesson,
-                      LessonsProvider, ThemeContext, ThemeProvider, Toolbar, use_lessons, use_theme, html_page};
+use pali_course_core::model::{Course, Exercise, Lesson};
+use pali_course_core::sandhi;
+
+use crate::{get_lessons_json, html_if_some, log_dbg, log_display, log_str};
+use crate::contexts::{ExerciseComponent, Exercises, QuizPage, ReviewPage,
+                      LessonsProvider, LessonsState, InputSchemeProvider, ScriptProvider, ThemeContext, ThemeProvider, Toolbar, use_lessons, use_theme, html_page};
 
 #[derive(Clone, Routable, PartialEq)]
 pub enum Route {
@@ -22,23 +25,43 @@ pub enum Route {
     #[at("/pali/resources")]
     LearningResources,
 
-    #[at("/pali/lessons")]
-    Lessons,
+    #[at("/pali/review")]
+    Review,
 
-    #[at("/pali/lesson/:path")]
-    Lesson { path: String },
+    #[at("/:course/lessons")]
+    Lessons { course: String },
+
+    #[at("/:course/lesson/:path")]
+    Lesson { course: String, path: String },
+
+    #[at("/:course/lessons/:path")]
+    RedirectToLesson { course: String, path: String },
+
+    #[at("/:course/lesson/:path/exercise/404")]
+    RedirectToLesson2 { course: String, path: String },
+
+    #[at("/:course/quiz")]
+    Quiz { course: String },
+
+    #[at("/:course/category/:category")]
+    ExerciseCategory { course: String, category: String },
 
-    #[at("/pali/lessons/:path")]
-    RedirectToLesson { path: String },
+    #[at("/:course/lesson/:lesson_path/exercise/:exercise_path")]
+    Exercise { course: String, lesson_path: String, exercise_path: String },
 
-    #[at("/pali/lesson/:path/exercise/404")]
-    RedirectToLesson2 { path: String },
+    // from before there were courses, these go to the first course
+
+    #[at("/pali/lessons")]
+    OldLessons,
+
+    #[at("/pali/lesson/:path")]
+    OldLesson { path: String },
 
     #[at("/pali/category/:category")]
-    ExerciseCategory { category: ExerciseCategory },
+    OldExerciseCategory { category: String },
 
     #[at("/pali/lesson/:lesson_path/exercise/:exercise_path")]
-    Exercise { lesson_path: String, exercise_path: String },
+    OldExercise { lesson_path: String, exercise_path: String },
 
     // #[at("/pali/lessons/:exercise_path")]
     // RedirectToExercise { path: String },
@@ -151,7 +174,11 @@ fn switch(route: Route) -> Html {
     return html! {
         <LessonsProvider>
             <ThemeProvider>
-                <SwitchLessons route={route} />
+                <InputSchemeProvider>
+                    <ScriptProvider>
+                        <SwitchLessons route={route} />
+                    </ScriptProvider>
+                </InputSchemeProvider>
             </ThemeProvider>
         </LessonsProvider>
     }
@@ -162,10 +189,23 @@ pub struct SwitchLessonsProps {
     pub route: Route,
 }
 
+fn unknown_course() -> Html {
+    content_from( html! { <> <h1> { "Unknown course" } </h1> <Link<Route> to={Route::Overview}> {"Return"} </Link<Route>> </> } )
+}
+
 #[styled_component(SwitchLessons)]
 pub fn switch_with_lessons(props: &SwitchLessonsProps) -> Html {
     let lessons_ctx = use_lessons();
-    let lessons = lessons_ctx.get_lessons();
+    let courses = match lessons_ctx.state() {
+        LessonsState::Loaded(courses) => courses,
+        LessonsState::Loading => return content_from(html! {
+            <div class="flexer"><p>{ "Loading lessons..." }</p></div>
+        }),
+        LessonsState::Failed(message) => return content_titled(String::from("Couldn't load lessons"), None, html! { <>
+            <div class="info"><span>{ "The lesson files have a mistake in them:" }</span></div>
+            <pre class="preserved">{ message }</pre>
+        </> }),
+    };
 
     match props.route.clone() {
         Route::RedirectFromHome => html! { <Redirect<Route> to={Route::Overview} /> },
@@ -182,7 +222,14 @@ pub fn switch_with_lessons(props: &SwitchLessonsProps) -> Html {
                             You can use this to memorize vocab, familiazize yourself, or quiz knowledge to track progression.
                             You should firstly look through the tutorial and options."#}</span>
                 <br/>
-                <h2 class="linked" > <Link<Route> to={Route::Lessons}>{ "View Lessons" }</Link<Route>> </h2>
+                <br/>
+                <span>{"Cells you reveal or check come back for "}</span>
+                <Link<Route> to={Route::Review}>{ "review" }</Link<Route>>
+                <span>{" when you're due to forget them, and sooner when you get them wrong."}</span>
+                <br/>
+                { for courses.courses.iter().map(|course| html! {
+                    <h2 class="linked" > <Link<Route> to={Route::Lessons {course: course.path.clone()}}>{ course.name.clone() }</Link<Route>> </h2>
+                }) }
                 <br/>
                 <span>{"I'll keep this "}</span>
                 <a class="linked" target="_blank" href="https://github.com/Branzz/pali-course">{"open source"}</a>
@@ -198,17 +245,18 @@ pub fn switch_with_lessons(props: &SwitchLessonsProps) -> Html {
                     <li> { "Framework for lesson creation" } </li>
                     <li> { "Exercise modes" } </li>
                     <li> { "Dark/Light theme" } </li>
+                    <li> { "Reveal-by-letter mode" } </li>
+                    <li> { "Shuffle rows" } </li>
+                    <li> { "Multiple answers in one cell" } </li>
+                    <li> { "Spaced repetition review" } </li>
                 </ul>
                 <ul class="boxxy">
                     <h3> { "In-progress" } </h3>
                     <li> { "Lessons 6+" } </li>
-                    <li> { "Reveal-by-letter mode" } </li>
                 </ul>
                 <ul class="boxxy">
                     <h3> { "Planned / Other ideas" } </h3>
                     <li> { "Verb root meanings exercise" } </li>
-                    <li> { "Multiple answers in one cell" } </li>
-                    <li> { "Shuffle rows" } </li>
                     <li> { "Show the lesser definitions" } </li>
                 </ul>
             </div>
@@ -228,64 +276,98 @@ pub fn switch_with_lessons(props: &SwitchLessonsProps) -> Html {
             <div class="flexer"><p>{ "Chart of sounds in the mouth (bottom ones don't exist)" }</p></div>
             <div class="centered preserved"> <img src="/assets/phoen.png" /> </div>
             <div class="centered preserved"> <img src="/assets/sandhi.png" /> </div>
+            <div class="flexer"><p>{ "Sandhi, what happens where words meet (the same rules the sandhi exercises check with)" }</p></div>
+            <div class="flexer">
+                <table class="exercise-table"> { for sandhi::chart().iter().map(|row| html! {
+                    <tr> { for row.iter().map(|cell| html! { <td> { cell } </td> }) } </tr>
+                }) } </table>
+            </div>
         </> }),
-        Route::Lessons => {
-            content_titled(String::from("Lessons"), Some(Route::Overview), html! { <>
+        Route::Review => html! { <ReviewPage courses={courses} /> },
+        Route::Lessons { course } => {
+            let Some(course) = courses.get(course.as_str()) else { return unknown_course() };
+            let warnings = course.warnings();
+            content_titled(course.name.clone(), Some(Route::Overview), html! { <>
                 <br/>
                 <div class="listed-info wide-text">
-                    { for lessons.lessons.iter().map(|lesson| html! {
-                        <Link<Route> to={Route::Lesson {path: lesson.path.clone()}}> { lesson.name.clone() } </Link<Route>>
+                    { for course.lessons.iter().map(|lesson| html! {
+                        <Link<Route> to={Route::Lesson {course: course.path.clone(), path: lesson.path.clone()}}> { lesson.name.clone() } </Link<Route>>
                     }) }
                 <br/>
                 <h3> { "Categories" } </h3>
-                    { for ExerciseCategory::iterator().map(|category| html! {
-                        <Link<Route> to={Route::ExerciseCategory {category: category.clone()}}> { category.to_proper_string() } </Link<Route>>
+                    { for course.categories.iter().map(|category| html! {
+                        <Link<Route> to={Route::ExerciseCategory {course: course.path.clone(), category: category.id.clone()}}> { category.name.clone() } </Link<Route>>
                     }) }
+                <br/>
+                <Link<Route> to={Route::Quiz {course: course.path.clone()}}> { "Mixed quiz" } </Link<Route>>
                 </div>
+                if !warnings.is_empty() {
+                    <div class="info">
+                        <h3> { "Lesson data warnings" } </h3>
+                        { for warnings.iter().map(|warning| html! { <p> { warning } </p> }) }
+                    </div>
+                }
             </>})
         },
-        Route::RedirectToLesson  { path } => html! { <Redirect<Route> to={Route::Lesson {path: path}} /> },
-        Route::RedirectToLesson2 { path } => html! { <Redirect<Route> to={Route::Lesson {path: path}} /> },
-        Route::Lesson { path } => {
-            let lesson_position_opt = lessons.lessons.iter().position(|l: &Lesson| l.path == path);
+        Route::RedirectToLesson  { course, path } => html! { <Redirect<Route> to={Route::Lesson {course, path}} /> },
+        Route::RedirectToLesson2 { course, path } => html! { <Redirect<Route> to={Route::Lesson {course, path}} /> },
+        Route::Lesson { course, path } => {
+            let Some(course) = courses.get(course.as_str()) else { return unknown_course() };
+            let lesson_position_opt = course.lessons.iter().position(|l: &Lesson| l.path == path);
             if lesson_position_opt.is_none() {
-                return content_from( html! { <> <h1> { "Unknown lesson" } </h1> <Link<Route> to={Route::Lessons}> {"Return"} </Link<Route>> </> } )
+                return content_from( html! { <> <h1> { "Unknown lesson" } </h1> <Link<Route> to={Route::Lessons {course: course.path.clone()}}> {"Return"} </Link<Route>> </> } )
             }
             let lesson_position = lesson_position_opt.unwrap();
 
-            let return_route = Some(Route::Lessons);
-            let prev_path = if lesson_position == 0 {None} else {lessons.lessons.get(lesson_position - 1).map(|l: &Lesson| l.path.clone())};
-            let next_path = lessons.lessons.get(lesson_position + 1).map(|l: &Lesson| l.path.clone());
-            let lesson: Lesson = (*lessons.lessons.get(lesson_position).unwrap()).clone();
+            let return_route = Some(Route::Lessons {course: course.path.clone()});
+            let prev_path = if lesson_position == 0 {None} else {course.lessons.get(lesson_position - 1).map(|l: &Lesson| l.path.clone())};
+            let next_path = course.lessons.get(lesson_position + 1).map(|l: &Lesson| l.path.clone());
+            let lesson: Lesson = (*course.lessons.get(lesson_position).unwrap()).clone();
 
-            let prev_route = prev_path.map(|path| Route::Lesson { path });
-            let next_route = next_path.map(|path| Route::Lesson { path });
+            let prev_route = prev_path.map(|path| Route::Lesson { course: course.path.clone(), path });
+            let next_route = next_path.map(|path| Route::Lesson { course: course.path.clone(), path });
 
             content_from_toolbar(
                 html! {
                     <Toolbar name={lesson.name} return_route={return_route} prev_route={prev_route} next_route={next_route}/>
                 },
                 html! {
-                    <Exercises lesson_path={Some(path)} exercises={lesson.exercises}/>
+                    <Exercises course_path={course.path.clone()} reference={course.reference.clone()} categories={course.categories.clone()} lesson_path={Some(path)} exercises={lesson.exercises}/>
                 }
             )
         },
-        Route::ExerciseCategory { category } => {
-            let exercises: Vec<Exercise> = lessons.lessons.iter()
+        Route::ExerciseCategory { course, category } => {
+            let Some(course) = courses.get(course.as_str()) else { return unknown_course() };
+            let Some(category) = course.category(category.as_str()) else {
+                return content_from( html! { <> <h1> { "Unknown category" } </h1> <Link<Route> to={Route::Lessons {course: course.path.clone()}}> {"Return"} </Link<Route>> </> } )
+            };
+            let exercises: Vec<Exercise> = course.lessons.iter()
                 .flat_map(|l: &Lesson| l.exercises.clone())
-                .filter(|e: &Exercise| e.categories.as_ref().map(|cs: &Vec<ExerciseCategory>| cs.contains(&category)).unwrap_or(false))
+                .filter(|e: &Exercise| e.is_in(category))
                 .collect();
-            // let category_lesson = Lesson {
-            //     name: category.to_string(),
-            //     path: category.to_string(),
-            //     exercises
-            // };
-            content_titled(category.to_proper_string(), Some(Route::Lessons), html! {
-                <Exercises lesson_path={category.to_string()} exercises={exercises}/>
-            })
+            let description = html_if_some(category.description.clone(), |description| html! { <div class="flexer"> <p class="info">{ description } </p> </div> });
+            content_titled(category.name.clone(), Some(Route::Lessons {course: course.path.clone()}), html! { <>
+                { description }
+                <Exercises course_path={course.path.clone()} reference={course.reference.clone()} categories={course.categories.clone()} lesson_path={category.id.clone()} exercises={exercises}/>
+            </> })
         }
-        Route::Exercise { lesson_path, exercise_path } => {
-            html_page(lessons, lesson_path, exercise_path)
+        Route::Quiz { course } => {
+            let Some(course) = courses.get(course.as_str()) else { return unknown_course() };
+            html! { <QuizPage course={course.clone()} /> }
+        },
+        Route::Exercise { course, lesson_path, exercise_path } => {
+            let Some(course) = courses.get(course.as_str()) else { return unknown_course() };
+            html_page(course.clone(), lesson_path, exercise_path)
+        },
+        Route::OldLessons | Route::OldLesson { .. } | Route::OldExerciseCategory { .. } | Route::OldExercise { .. } => {
+            let Some(course) = courses.courses.first().map(|c: &Course| c.path.clone()) else { return unknown_course() };
+            let to = match props.route.clone() {
+                Route::OldLesson { path } => Route::Lesson { course, path },
+                Route::OldExerciseCategory { category } => Route::ExerciseCategory { course, category },
+                Route::OldExercise { lesson_path, exercise_path } => Route::Exercise { course, lesson_path, exercise_path },
+                _ => Route::Lessons { course },
+            };
+            html! { <Redirect<Route> to={to} /> }
         },
         Route::NotFound => content_titled("404".to_string(), Some(Route::Overview), html! {
             <h1>{ "Not found" }</h1>
@@ -1,3 +1,3 @@
 mod app;
 
-pub(crate) use app::{main, Route, empty_html, content_from, content_from_toolbar};
+pub(crate) use app::{main, Route, empty_html, content_from, content_from_toolbar, content_titled};
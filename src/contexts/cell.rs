@@ -1,64 +1,113 @@
+use gloo_events::EventListener;
 use web_sys::{HtmlInputElement, MouseEvent};
 use yew::{Component, Context, Html, html, Properties};
 use yew::prelude::*;
 use std::collections::HashMap;
+use pali_course_core::check::{CheckStrictness, check_answer};
+use pali_course_core::options::rank_distractors;
+use pali_course_core::table::{Location, TriSplit};
+use rand::seq::SliceRandom;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{log, log_display, log_js, get_text_width};
-use crate::contexts::table::{Location};
-use crate::contexts::{TriSplit, ThemeKind};
+use crate::contexts::{InputScheme, Script, ThemeKind};
 
 #[derive(Properties, PartialEq)]
 pub struct SpoilerCellProps {
     pub theme: ThemeKind,
     pub class: String,
     pub text: TriSplit,
+    pub revealed: bool,
+    pub onflip: Callback<()>,
 }
 
-pub struct SpoilerCell {
-    spoiled: bool,
-}
-
-pub enum SpoilerCellMsg {
-    FlipState,
-    Spoil,
-    Reveal,
-}
+/**
+ * Shows or hides the middle when clicked; whether it's shown is kept by the table, so whole rows and columns can be flipped at once.
+ */
+pub struct SpoilerCell;
 
 impl Component for SpoilerCell {
-    type Message = SpoilerCellMsg;
+    type Message = ();
     type Properties = SpoilerCellProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self { spoiled: true }
+        Self
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
-            SpoilerCellMsg::FlipState => { self.spoiled = !self.spoiled; true },
-            SpoilerCellMsg::Spoil => { if self.spoiled { false } else { self.spoiled = false; true }},
-            SpoilerCellMsg::Reveal => { if !self.spoiled { false } else { self.spoiled = true; true }},
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.props().onflip.reform(move |_e: MouseEvent| ());
+
+        let spoil_class = if ctx.props().revealed { "spoiler_button visible" } else { "spoiler_button invisible" };
+        let text = ctx.props().text.clone();
+        let mut td_class = ctx.props().class.clone();
+        td_class.push_str(" clickable");
+
+        return html! {
+            <td class={td_class} onmousedown={onclick}>
+                { text.start } <span class={spoil_class}> { text.middle } </span> { text.end }
+            </td>
         }
     }
 
+}
+
+#[derive(Properties, PartialEq)]
+pub struct LetterCellProps {
+    pub theme: ThemeKind,
+    pub class: String,
+    pub text: TriSplit,
+    pub revealed: usize,
+    pub onreveal: Callback<()>,
+}
+
+/**
+ * Reveals the middle one grapheme at a time; the revealed count is kept by the table.
+ */
+pub struct LetterCell;
+
+impl Component for LetterCell {
+    type Message = ();
+    type Properties = LetterCellProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let onclick = ctx.link().callback(move |_e: MouseEvent| {
-            SpoilerCellMsg::FlipState
+        let onreveal = ctx.props().onreveal.clone();
+        let onclick = onreveal.reform(move |_e: MouseEvent| ());
+        let onkeydown = Callback::from(move |e: KeyboardEvent| {
+            match e.key().as_str() {
+                "Tab" | "Shift" | "Control" | "Alt" | "Meta" => {},
+                _ => { e.prevent_default(); onreveal.emit(()) },
+            }
         });
 
-        let spoil_class = if self.spoiled { "spoiler_button invisible" } else { "spoiler_button visible" };
         let text = ctx.props().text.clone();
+        let letters = graphemes(text.middle.as_str());
+        let revealed = ctx.props().revealed.min(letters.len());
+        let shown: String = letters[..revealed].concat();
+        let hidden: String = letters[revealed..].concat();
+
         let mut td_class = ctx.props().class.clone();
         td_class.push_str(" clickable");
 
         return html! {
-            <td class={td_class} onmousedown={onclick.clone()}>
-                { text.start } <span class={spoil_class} onmousedown={onclick}> { text.middle } </span> { text.end }
+            <td class={td_class} tabindex="0" onmousedown={onclick} onkeydown={onkeydown}>
+                { text.start } { shown }<span class={ctx.props().theme.css_class_themed("spoiler")}>{ hidden }</span> { text.end }
             </td>
         }
     }
 
 }
 
+/**
+ * "bhavāmi" -> ["b", "h", "a", "v", "ā", "m", "i"], keeping combining diacritics on their letter
+ */
+pub(crate) fn graphemes(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
 pub(crate) const DEFAULT_SELECTION_STRING: String = String::new();
 
 #[derive(Properties, PartialEq)]
@@ -66,8 +115,12 @@ pub struct DropDownCellProps {
     pub class: String,
     pub text: TriSplit,
     pub options: Vec<String>,
+    pub script: Script, // of the options' labels
     pub location: Location,
     pub check_mode: bool,
+    pub strictness: CheckStrictness,
+    pub initial: String,
+    pub onchange: Callback<String>,
 }
 
 pub struct DropDownCell {
@@ -82,15 +135,19 @@ impl Component for DropDownCell {
     type Message = DropDownCellMsg;
     type Properties = DropDownCellProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
         Self {
-            selected: DEFAULT_SELECTION_STRING,
+            selected: ctx.props().initial.clone(),
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            DropDownCellMsg::Update(value) => { self.selected = value; true }
+            DropDownCellMsg::Update(value) => {
+                ctx.props().onchange.emit(value.clone());
+                self.selected = value;
+                true
+            }
         }
     }
 
@@ -103,15 +160,15 @@ impl Component for DropDownCell {
             DropDownCellMsg::Update(input.value())
         });
 
-        let checked_class = check_input(ctx.props().check_mode, self.selected.clone(), ctx.props().text.middle.clone());
+        let checked_class = check_input(ctx.props().check_mode, self.selected.clone(), &ctx.props().text.answers, &ctx.props().strictness);
         // let theme = &ctx.props().theme;
 
         return html! {
             <td class={checked_class}> { text.start }
                 <select class={ctx.props().class.clone()} onchange={dropdown_changed.clone()} required={true}>
-                    <option value={DEFAULT_SELECTION_STRING.clone()} disabled={true} selected={true} hidden={true}> {DEFAULT_SELECTION_STRING.clone()} </option>
+                    <option value={DEFAULT_SELECTION_STRING.clone()} disabled={true} selected={self.selected == DEFAULT_SELECTION_STRING} hidden={true}> {DEFAULT_SELECTION_STRING.clone()} </option>
                     { for (&ctx).props().options.iter().map(|o| { html! {
-                        <option value={o.clone()}>{o}</option>
+                        <option value={o.clone()} selected={o == &self.selected}>{ctx.props().script.transliterate(o.as_str())}</option>
                     } }) }
                 </select>
             { text.end } </td>
@@ -120,9 +177,96 @@ impl Component for DropDownCell {
 
 }
 
+/// besides the answer, so there are four to choose from
+const DISTRACTORS: usize = 3;
+
+#[derive(Properties, PartialEq)]
+pub struct MultipleChoiceCellProps {
+    pub class: String,
+    pub text: TriSplit,
+    pub options: Vec<String>, // of the answer's column
+    pub script: Script, // of the choices' labels
+    pub strictness: CheckStrictness,
+    pub initial: String,
+    pub onchange: Callback<String>,
+}
+
+/**
+ * The answer among the options most like it, checked as soon as one is picked
+ */
+pub struct MultipleChoiceCell {
+    pub choices: Vec<String>,
+    pub selected: String,
+}
+
+pub enum MultipleChoiceCellMsg {
+    Choose(String),
+}
+
+impl Component for MultipleChoiceCell {
+    type Message = MultipleChoiceCellMsg;
+    type Properties = MultipleChoiceCellProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            choices: multiple_choices(&ctx.props().text, &ctx.props().options),
+            selected: ctx.props().initial.clone(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MultipleChoiceCellMsg::Choose(value) => {
+                if self.selected == value {
+                    return false;
+                }
+                ctx.props().onchange.emit(value.clone());
+                self.selected = value;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let text = ctx.props().text.clone();
+        let checked_class = check_input(true, self.selected.clone(), &text.answers, &ctx.props().strictness);
+
+        return html! {
+            <td class={checked_class}> { text.start }
+                <span class="choices">
+                    { for self.choices.iter().map(|choice| {
+                        let value = choice.clone();
+                        let onclick = ctx.link().callback(move |_: MouseEvent| MultipleChoiceCellMsg::Choose(value.clone()));
+                        let chosen = (choice == &self.selected).then_some("chosen");
+                        html! {
+                            <button class={classes!(ctx.props().class.clone(), "choice", chosen)} onclick={onclick}>{ ctx.props().script.transliterate(choice.as_str()) }</button>
+                        }
+                    }) }
+                </span>
+            { text.end } </td>
+        }
+    }
+
+}
+
+/**
+ * The answer and the options most like it, shuffled. They're in Roman like the options, since the middle is in the
+ * cell's script; only their labels are transliterated.
+ */
+fn multiple_choices(text: &TriSplit, options: &[String]) -> Vec<String> {
+    let answer = text.answers[0].clone(); // as written, alternatives and all
+    let mut choices = rank_distractors(answer.as_str(), options, DISTRACTORS);
+    choices.push(answer);
+    choices.shuffle(&mut rand::thread_rng());
+    choices
+}
+
 pub struct TypeFieldCell {
     pub content: String,
     pub width: i32,
+    pub undo: Option<Conversion>, // the last conversion, until something else is typed
+    input: NodeRef,
+    _composition_listener: Option<EventListener>, // yew has no oncompositionend
 }
 
 #[derive(Properties, PartialEq)]
@@ -131,27 +275,50 @@ pub struct TypeFieldCellProps {
     pub text: TriSplit,
     pub check_mode: bool,
     pub size: i32,
+    pub scheme: Option<InputScheme>, // None in columns that aren't Pāli, so English isn't converted
+    pub strictness: CheckStrictness,
+    pub initial: String,
+    pub onchange: Callback<String>,
+}
+
+/**
+ * "bhavaa|" -> "bhavā|" (| being the caret); kept so backspace can put the typed letters back
+ */
+#[derive(Clone)]
+pub struct Conversion {
+    converted: String,
+    caret: u32,
+    raw: String,
+    raw_caret: u32,
 }
 
 pub enum TypeFieldCellMsg {
-    Update(String, i32)
+    Update(String, i32),
+    Converted(Conversion),
+    Undo(String),
+    Composed, // by an IME or a phone keyboard, which don't send "insertText"
 }
 
 impl Component for TypeFieldCell {
     type Message = TypeFieldCellMsg;
     type Properties = TypeFieldCellProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
         Self {
-            content: DEFAULT_SELECTION_STRING,
+            content: ctx.props().initial.clone(),
             width: 20,
+            undo: None,
+            input: NodeRef::default(),
+            _composition_listener: None,
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             TypeFieldCellMsg::Update(value, width) => {
+                self.undo = None;
                 if self.content != value {
+                    ctx.props().onchange.emit(value.clone());
                     self.content = value;
                     self.width = width;
                     true
@@ -159,32 +326,68 @@ impl Component for TypeFieldCell {
                     false
                 }
             }
+            TypeFieldCellMsg::Converted(conversion) => {
+                ctx.props().onchange.emit(conversion.converted.clone());
+                self.content = conversion.converted.clone();
+                self.undo = Some(conversion);
+                true
+            }
+            TypeFieldCellMsg::Undo(raw) => {
+                ctx.props().onchange.emit(raw.clone());
+                self.content = raw;
+                self.undo = None;
+                true
+            }
+            TypeFieldCellMsg::Composed => {
+                let Some(input) = self.input.cast::<HtmlInputElement>() else { return false };
+                let scheme = ctx.props().scheme.clone();
+                let msg = convert_input(&input, |before| convert_all(&scheme, before));
+                Component::update(self, ctx, msg)
+            }
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if let (true, Some(input)) = (first_render, self.input.cast::<HtmlInputElement>()) {
+            let composed = ctx.link().callback(|_: ()| TypeFieldCellMsg::Composed);
+            self._composition_listener = Some(EventListener::new(&input, "compositionend", move |_| composed.emit(())));
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let text = ctx.props().text.clone();
 
-        let paste = ctx.link().callback(move |e: Event| {
+        // pasted text comes through here too, as an "insertFromPaste". Text from an IME or a phone keyboard is
+        // converted once it's composed, see TypeFieldCellMsg::Composed
+        let scheme = ctx.props().scheme.clone();
+        let content_changed = ctx.link().callback(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
-            let text_width: i32 = 0; // get_text_width(input.target);
-            // input.set_length()
-
-            TypeFieldCellMsg::Update(input.value(), text_width)
+            match e.input_type().as_str() {
+                "insertText" => convert_input(&input, |before| scheme.as_ref().and_then(|scheme| scheme.convert_suffix(before))),
+                "insertFromPaste" | "insertFromDrop" | "insertReplacementText" => convert_input(&input, |before| convert_all(&scheme, before)),
+                _ => TypeFieldCellMsg::Update(input.value(), 0), // get_text_width(input); // TODO
+            }
         });
 
-
-        let content_changed = ctx.link().callback(move |e: InputEvent| {
+        let undo = self.undo.clone();
+        let undo_conversion = ctx.link().batch_callback(move |e: KeyboardEvent| {
+            let undo = undo.as_ref().filter(|_| e.key() == "Backspace")?;
             let input: HtmlInputElement = e.target_unchecked_into();
-
-            let text_width: i32 = 0; // get_text_width(input); // TODO
-
-            TypeFieldCellMsg::Update(input.value(), text_width)
+            let selection = (input.selection_start().ok().flatten(), input.selection_end().ok().flatten());
+            if input.value() != undo.converted || selection != (Some(undo.caret), Some(undo.caret)) {
+                return None;
+            }
+            e.prevent_default();
+            input.set_value(undo.raw.as_str());
+            let _ = input.set_selection_range(undo.raw_caret, undo.raw_caret);
+            Some(TypeFieldCellMsg::Undo(undo.raw.clone()))
         });
 
         // let width: String = format!("{}px", self.width + 4);
 
-        let checked_class = check_input(ctx.props().check_mode, self.content.clone(), ctx.props().text.middle.clone());
+        // what wasn't converted while typing, like answers saved before the scheme was changed
+        let typed = convert_all(&ctx.props().scheme, self.content.as_str()).unwrap_or(self.content.clone());
+        let checked_class = check_input(ctx.props().check_mode, typed, &ctx.props().text.answers, &ctx.props().strictness);
 
         // TODO lengthen fields when typed into - https://jsfiddle.net/drq0nz6j/
         let class = ctx.props().class.clone();
@@ -193,76 +396,75 @@ impl Component for TypeFieldCell {
 
         return html! {
             <td class={checked_class}> { text.start }
-                <input type="text" class={class} oninput={content_changed} onpaste={paste} size={size} /> { text.end } // onchange will wait until cell unfocused
+                <input type="text" class={class} value={self.content.clone()} ref={self.input.clone()} oninput={content_changed} onkeydown={undo_conversion} size={size} /> { text.end } // onchange will wait until cell unfocused
             </td>
         }
     }
 
 }
 
-fn check_input(check_mode: bool, content: String, answer: String) -> &'static str {
-    if check_mode {
-        let content = convert_iso_shorthand(content);
-
-        if content.len() == 0 {
-            return ""; // content == DEFAULT_SELECTION_STRING
-        }
-
-        let content_bytes = content.as_bytes();
+/// None when there's nothing to convert
+fn convert_all(scheme: &Option<InputScheme>, text: &str) -> Option<String> {
+    scheme.as_ref().map(|scheme| scheme.convert(text.to_string())).filter(|converted| converted != text)
+}
 
-        let mut content_start: usize = 0;
-        while content_bytes[content_start] == (32 as u8) {
-            content_start += 1;
-            if content_start == content_bytes.len() {
-                return "";
-            }
+/// converts what's before the caret, keeping what it was so it can be undone
+fn convert_input(input: &HtmlInputElement, convert: impl Fn(&str) -> Option<String>) -> TypeFieldCellMsg {
+    let value = input.value();
+    let caret = input.selection_start().ok().flatten().unwrap_or(utf16_len(value.as_str()));
+    let (before, after) = value.split_at(byte_index(value.as_str(), caret));
+    match convert(before) {
+        None => TypeFieldCellMsg::Update(value, 0),
+        Some(converted_before) => {
+            let converted_caret = utf16_len(converted_before.as_str());
+            let converted = format!("{}{}", converted_before, after);
+            input.set_value(converted.as_str());
+            let _ = input.set_selection_range(converted_caret, converted_caret);
+            TypeFieldCellMsg::Converted(Conversion { converted, caret: converted_caret, raw: value.clone(), raw_caret: caret })
         }
+    }
+}
 
-        let mut content_end: usize = content_bytes.len() - 1;
-        while content_bytes[content_end] == (32 as u8) {
-            content_end -= 1;
-        }
+/// the input's caret is counted in utf-16
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
 
-        // if content_start >= content_end + 1 { // " content " == DEFAULT_SELECTION_STRING
-        //     return "";
-        // }
-        let answer_bytes = answer.as_bytes();
-        if content_end + 1 - content_start != answer_bytes.len() {
-            return "incorrect_cell";
-        }
-        let mut i: usize = 0;
-        while i < answer_bytes.len() {
-            if content_bytes[i + content_start] != answer_bytes[i] {
-                return "incorrect_cell";
-            }
-            i = i + 1;
+fn byte_index(text: &str, utf16_index: u32) -> usize {
+    let mut units: u32 = 0;
+    for (index, c) in text.char_indices() {
+        if units >= utf16_index {
+            return index;
         }
-        return "correct_cell";
-
-    } else {
-        ""
+        units += c.len_utf16() as u32;
     }
-
+    text.len()
 }
 
+/// correct if the content matches any of the answers
+pub(crate) fn check_input(check_mode: bool, content: String, answers: &Vec<String>, strictness: &CheckStrictness) -> &'static str {
+    if !check_mode {
+        return "";
+    }
+    match check_answer(content.as_str(), answers, strictness) {
+        Some(true) => "correct_cell",
+        Some(false) => "incorrect_cell",
+        None => "", // content == DEFAULT_SELECTION_STRING
+    }
+}
 
-// match the description in main.js
-const ISO_MAP: [(&str, &str); 10] = [
-    ("aa", "ā"),
-    ("ii", "ī"),
-    ("uu", "ū"),
-    (".t", "ṭ"),
-    (".d", "ḍ"),
-    ("`n", "ṅ"),
-    ("~n", "ñ"),
-    (".n", "ṇ"),
-    (".m", "ṃ"),
-    (".l", "ḷ"),
-];
-
-fn convert_iso_shorthand(mut shorthand: String) -> String {
-    for (from, to) in &ISO_MAP {
-        shorthand = shorthand.replace(from, to);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choices_stay_roman_in_other_scripts() {
+        let text = Script::Devanagari.transliterate_split(&TriSplit::new("bhav".to_string(), "āmi".to_string(), String::new()));
+        assert_ne!(text.middle, "āmi");
+        let options: Vec<String> = ["ati", "asi", "āmi", "āma"].iter().map(|o| o.to_string()).collect();
+        let choices = multiple_choices(&text, &options);
+        assert_eq!(choices.len(), 4);
+        assert_eq!(choices.iter().filter(|choice| *choice == "āmi").count(), 1);
+        assert_eq!(check_answer("āmi", &text.answers, &CheckStrictness::Exact), Some(true));
     }
-    shorthand
 }
@@ -1,7 +1,4 @@
-use std::fmt::{Display, Formatter};
-use std::fmt;
 use std::ops::Deref;
-use std::slice::Iter;
 use std::str::FromStr;
 use std::str::pattern::{Pattern, Searcher, SearchStep};
 
@@ -17,41 +14,45 @@ use yew_router::prelude::*;
 
 use crate::{html_if_some, log_display, log_str};
 use crate::app::{content_from, content_from_toolbar, empty_html, Route};
-use crate::contexts::{Lesson, ThemeContext, ThemeKind, TriSplit, use_lessons, use_theme, Table, TableLayout, Toolbar, SpoilerCell, SpoilerCellProps, LessonsContext, Lessons};
-use crate::contexts::exercise::ExerciseCategory::*;
-use crate::contexts::table::ExerciseMode;
-
-#[derive(PartialEq, Clone, Deserialize)]
-pub struct Exercise {
-    pub exercise_level: Option<String>,
-    pub categories: Option<Vec<ExerciseCategory>>,
-    pub info: Option<String>,
-    pub title: Option<String>,
-    pub path: Option<String>, // how to refer to it in the url
-    pub table_layout: Option<TableLayout>,
-    pub explanation: Option<String>,
-    pub page: Option<i32>,
-}
-
-impl Exercise {
-
-    pub fn effective_path(&self) -> String {
-        // .map(|mut t: String| {t.remove_matches(|c: char| c.is_whitespace()); t})
-        self.path.clone().or(self.title.clone()).unwrap_or("404".to_string())
+use crate::contexts::{ThemeContext, ThemeKind, use_lessons, use_theme, Table, Toolbar, SpoilerCell, SpoilerCellProps, LessonsContext, InputSchemeContext, InputSchemeSwitcher, use_input_scheme, ScriptContext, ScriptSwitcher, use_script};
+use pali_course_core::crosscheck::cross_check;
+use pali_course_core::model::{BookReference, Course, Exercise, ExerciseCategory, ExerciseMode, GeneratedTable, Lesson, TableLayout};
+use pali_course_core::review::ReviewRows;
+
+fn generated_switcher(generated: &GeneratedTable) -> Html {
+    match generated {
+        GeneratedTable::InputScheme => html! { <div class="flexer"> <InputSchemeSwitcher /> </div> },
+        GeneratedTable::Script => html! { <div class="flexer"> <ScriptSwitcher /> </div> },
     }
-
 }
 
+fn script_sample() -> TableLayout {
+    let mut table_layout = TableLayout::new(vec![
+        vec!["Roman".to_string(), "script".to_string()],
+        vec!["kāya".to_string(), "kāya".to_string()],
+        vec!["bhavati".to_string(), "bhav|ati|".to_string()],
+        vec!["dhammo".to_string(), "dhamm|o|".to_string()],
+        vec!["saṃgho".to_string(), "saṃgh|o|".to_string()],
+    ]);
+    table_layout.pali_cols = Some(vec![1]);
+    table_layout.default_mode = Some(ExerciseMode::Show);
+    table_layout
+}
 
 #[derive(Properties, PartialEq)]
 pub struct ExerciseComponentProps {
+    pub course_path: String,
+    pub reference: Option<BookReference>,
     pub lesson_path: Option<String>,
     pub exercise: Exercise,
+    pub category_mode: Option<ExerciseMode>,
 }
 
 #[styled_component(ExerciseComponent)]
 pub(crate) fn exercise_component(props: &ExerciseComponentProps) -> Html {
     let theme: ThemeContext = use_theme();
+    let input_scheme: InputSchemeContext = use_input_scheme();
+    let script: ScriptContext = use_script();
 
     let is_important = props.exercise.exercise_level.clone()
                                         .map(|s: String| ExerciseLevel::from_str(s.as_str()))
@@ -60,7 +61,7 @@ pub(crate) fn exercise_component(props: &ExerciseComponentProps) -> Html {
                                         .unwrap_or(false);
 
     let link = html_if_some(props.lesson_path.clone(), |path| {
-        let exercise_link = Route::Exercise { lesson_path: path, exercise_path: props.exercise.effective_path() };
+        let exercise_link = Route::Exercise { course: props.course_path.clone(), lesson_path: path, exercise_path: props.exercise.effective_path() };
         html!(
             <div class={css!(r#"height: 20px;
                                 width: 20px;
@@ -105,13 +106,39 @@ pub(crate) fn exercise_component(props: &ExerciseComponentProps) -> Html {
         </div>
     });
 
-    let id_str = format!("{}-{}", props.lesson_path.clone().unwrap_or("anon".to_string()), props.exercise.title.clone().unwrap_or("anon".to_string()));
+    let id_str = props.exercise.id.clone();
     let table_id = id_str.as_str();
 
     // log_display(props.exercise.table_layout.clone().unwrap().table.get(0).unwrap().get(0).unwrap());
     let info = html_if_some(props.exercise.info.clone(), |info| html! { <div class="flexer"> <p class="info">{ info } </p> </div> });
-    let table = html_if_some(props.exercise.table_layout.clone(), |table_layout| html!{
-        <Table key={table_id} table_layout={table_layout.clone()} theme={theme.kind.clone()} categories={props.exercise.categories.clone().unwrap_or(vec![])} id={id_str.clone()}/>
+    let table_layout = match props.exercise.generated.clone() {
+        Some(GeneratedTable::InputScheme) => Some(input_scheme.table_layout()),
+        Some(GeneratedTable::Script) => Some(script_sample()),
+        None => props.exercise.table_layout.clone(),
+    };
+    let table_key = match props.exercise.generated {
+        Some(GeneratedTable::InputScheme) => format!("{}-{}", table_id, input_scheme.to_string()), // rebuilt when the scheme changes
+        _ => table_id.to_string(),
+    };
+    let table = html_if_some(table_layout, |table_layout| {
+        // generated tables change with the settings, so they aren't reviewed
+        let review = props.exercise.generated.is_none().then(|| ReviewRows::all(table_id, table_layout.table.len()));
+        html!{
+            <Table key={table_key.clone()} table_layout={table_layout.clone()} theme={theme.kind.clone()} category_mode={props.category_mode.clone()} id={id_str.clone()} review={review} input_scheme={input_scheme.scheme()} script={script.script()}/>
+        }
+    });
+    let switcher = html_if_some(props.exercise.generated.clone(), |generated| generated_switcher(&generated));
+    // only in debug builds, for whoever is writing the lessons
+    let paradigm_check = if cfg!(debug_assertions) { cross_check(&props.exercise) } else { None };
+    let paradigm_banner = html_if_some(paradigm_check.filter(|check| !check.mismatches.is_empty()), |check| html! {
+        <div class="flexer">
+            <div class="dev-banner">
+                <p> { format!("Cells that aren't forms of {}:", check.paradigm) } </p>
+                <ul> { for check.mismatches.iter().map(|mismatch| html! {
+                    <li> { format!("row {}, column {}: \"{}\", expected \"{}\"", mismatch.location.0 + 1, mismatch.location.1 + 1, mismatch.written, mismatch.expected) } </li>
+                }) } </ul>
+            </div>
+        </div>
     });
     let explanation = html_if_some(props.exercise.explanation.clone(), |explanation| {
         let mut explanation_class = theme.kind.css_class_themed("");
@@ -122,9 +149,9 @@ pub(crate) fn exercise_component(props: &ExerciseComponentProps) -> Html {
              </div>
         )
     });
-    let page = html_if_some(props.exercise.page.clone(), |page: i32| {
-        let ref_link = format!("https://archive.org/details/A.K.WarderPali/A.%20K.%20Warder%20Pali/page/n{}/mode/1up", page + 13); // preface offset
-        let hover_text = format!("Warder p. {}", page);
+    let page = html_if_some(props.exercise.page.zip(props.reference.clone()), |(page, reference): (i32, BookReference)| {
+        let ref_link = reference.page_url(page);
+        let hover_text = format!("{} p. {}", reference.name, page);
         html! (
             <div class="flexer">
                 <a class="ref" href={ref_link} title={hover_text} target="_blank"> {"Reference"} </a>
@@ -136,6 +163,8 @@ pub(crate) fn exercise_component(props: &ExerciseComponentProps) -> Html {
         <div class={css!(r#"margin-bottom: 50px;"#)}>
             { title }
             { info }
+            { switcher }
+            { paradigm_banner }
             { table }
             { page }
             { explanation }
@@ -209,66 +238,9 @@ impl Component for Explanation {
 
 }
 
-#[derive(PartialEq, Clone, Deserialize)]
-pub enum ExerciseCategory {
-    Conjugation, Tam, Verbs, Vocab, Aorist, Declension
-}
-
-impl Display for ExerciseCategory {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let to_string = match self {
-                Conjugation => "conjugation",
-                Tam => "tam",
-                Verbs => "verbs",
-                Vocab => "vocab",
-                Aorist => "aorist",
-                Declension => "declension",
-        };
-        f.write_str(to_string)
-    }
-}
-
-impl FromStr for ExerciseCategory {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "conjugation" => Ok(ExerciseCategory::Conjugation),
-            "tam" => Ok(ExerciseCategory::Tam),
-            "vocab" => Ok(ExerciseCategory::Vocab),
-            "verbs" => Ok(ExerciseCategory::Verbs),
-            "aorist" => Ok(ExerciseCategory::Aorist),
-            "declension" => Ok(ExerciseCategory::Declension),
-            _ => Err(()),
-        }
-    }
-}
-
-impl ExerciseCategory {
-    pub fn to_proper_string(&self) -> String {
-        match self {
-            Conjugation => "Conjugations",
-            Tam => "Tense-Aspect-Mood",
-            Verbs => "Verbs",
-            Vocab => "Vocab",
-            Aorist => "Aorist",
-            Declension => "Declension",
-        }.to_string()
-    }
-}
-
-impl ExerciseCategory {
-
-
-    pub(crate) fn iterator() -> Iter<'static, ExerciseCategory> {
-        static EXERCISE_CATEGORIES: [ExerciseCategory; 6] = [Conjugation, Tam, Verbs, Vocab, Aorist, Declension];
-        EXERCISE_CATEGORIES.iter()
-    }
-
-}
-
-pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: String) -> Html {
-    let lesson_position_opt = lessons.lessons.iter().position(|l: &Lesson| l.path == lesson_path);
+pub(crate) fn html_page(course: Course, lesson_path: String, exercise_path: String) -> Html {
+    let course_path = course.path.clone();
+    let lesson_position_opt = course.lessons.iter().position(|l: &Lesson| l.path == lesson_path);
 
     let prev_lesson_path: Option<String>;
     let prev_exercise_path: Option<String>;
@@ -279,23 +251,21 @@ pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: St
     let return_route: Option<Route>;
 
     if lesson_position_opt.is_none() {
-        let exercise_category = ExerciseCategory::iterator().find(|c: &&ExerciseCategory| c.to_string() == lesson_path);
+        let exercise_category = course.categories.iter().find(|c: &&ExerciseCategory| c.id == lesson_path);
         if exercise_category.is_some() {
             let exercise_category = exercise_category.unwrap();
-            let mut exercises: Vec<Exercise> = lessons.lessons.iter()
+            let mut exercises: Vec<Exercise> = course.lessons.iter()
                 .flat_map(|l: &Lesson| l.exercises.clone())
-                .filter(|e: &Exercise| e.categories.as_ref()
-                    .map(|cs: &Vec<ExerciseCategory>| cs.contains(&exercise_category))
-                    .unwrap_or(false))
+                .filter(|e: &Exercise| e.is_in(exercise_category))
                 .collect();
 
             let decoded_exercise_path = percent_decode_str(exercise_path.as_str()).decode_utf8().unwrap();
 
             let exercise_position_opt = exercises.iter().position(|e: &Exercise| e.effective_path() == decoded_exercise_path);
             if exercise_position_opt.is_none() {
-                return content_from( html! { <> <h1> { "Unknown exercise" } </h1> <Link<Route> to={Route::Lesson {path: lesson_path.clone()}}> { "Return" } </Link<Route>> </> } )
+                return content_from( html! { <> <h1> { "Unknown exercise" } </h1> <Link<Route> to={Route::Lesson {course: course_path.clone(), path: lesson_path.clone()}}> { "Return" } </Link<Route>> </> } )
             }
-            lesson_name = exercise_category.to_proper_string();
+            lesson_name = exercise_category.name.clone();
             let exercise_position = exercise_position_opt.unwrap();
 
             if exercise_position == 0 {
@@ -314,15 +284,15 @@ pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: St
             };
 
             exercise = exercises.remove(exercise_position);
-            return_route = Some(Route::ExerciseCategory { category: exercise_category.clone() });
+            return_route = Some(Route::ExerciseCategory { course: course_path.clone(), category: exercise_category.id.clone() });
 
         } else {
-            return content_from( html! { <> <h1> { "Unknown lesson" } </h1> <Link<Route> to={Route::Lessons} /> </> } )
+            return content_from( html! { <> <h1> { "Unknown lesson" } </h1> <Link<Route> to={Route::Lessons {course: course_path.clone()}}> { "Return" } </Link<Route>> </> } )
         }
     } else {
         let lesson_position = lesson_position_opt.unwrap();
 
-        let mut lesson: Lesson = (*lessons.lessons.get(lesson_position).unwrap()).clone();
+        let mut lesson: Lesson = (*course.lessons.get(lesson_position).unwrap()).clone();
         let lesson_path = lesson.path;
         lesson_name = lesson.name;
 
@@ -330,7 +300,7 @@ pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: St
 
         let exercise_position_opt = lesson.exercises.iter().position(|e: &Exercise| e.effective_path() == decoded_exercise_path);
         if exercise_position_opt.is_none() {
-            return content_from( html! { <> <h1> { "Unknown exercise" } </h1> <Link<Route> to={Route::Lesson {path: lesson_path.clone()}}> { "Return" } </Link<Route>> </> } )
+            return content_from( html! { <> <h1> { "Unknown exercise" } </h1> <Link<Route> to={Route::Lesson {course: course_path.clone(), path: lesson_path.clone()}}> { "Return" } </Link<Route>> </> } )
         }
         let exercise_position = exercise_position_opt.unwrap();
         if exercise_position == 0 {
@@ -338,7 +308,7 @@ pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: St
                 prev_lesson_path = None;
                 prev_exercise_path = None;
             } else {
-                let l: Option<&Lesson> = lessons.lessons.get((lesson_position as i32 - 1) as usize);
+                let l: Option<&Lesson> = course.lessons.get((lesson_position as i32 - 1) as usize);
                 prev_lesson_path = l.map(|l: &Lesson| l.path.clone());
                 prev_exercise_path = l.map(|l: &Lesson| l.exercises.last()).flatten().map(|e| e.effective_path().clone());
             }
@@ -348,11 +318,11 @@ pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: St
         };
 
         if exercise_position == lesson.exercises.len() - 1 {
-            if lesson_position == lessons.lessons.len() - 1 {
+            if lesson_position == course.lessons.len() - 1 {
                 next_lesson_path = None;
                 next_exercise_path = None;
             } else {
-                let l: Option<&Lesson> = lessons.lessons.get(lesson_position + 1);
+                let l: Option<&Lesson> = course.lessons.get(lesson_position + 1);
                 next_lesson_path = l.map(|l: &Lesson| l.path.clone());
                 next_exercise_path = l.map(|l: &Lesson| l.exercises.first()).flatten().map(|e| e.effective_path().clone());
             }
@@ -362,14 +332,14 @@ pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: St
         };
 
         exercise = lesson.exercises.remove(exercise_position);
-        return_route = Some(Route::Lesson { path: lesson_path.clone() });
+        return_route = Some(Route::Lesson { course: course_path.clone(), path: lesson_path.clone() });
     }
 
     let prev_route = prev_lesson_path.map(|lesson_path| prev_exercise_path.map(|exercise_path| {
-        Some(Route::Exercise { lesson_path, exercise_path })
+        Some(Route::Exercise { course: course_path.clone(), lesson_path, exercise_path })
     }).unwrap_or(None)).unwrap_or(None);
     let next_route = next_lesson_path.map(|lesson_path| next_exercise_path.map(|exercise_path| {
-        Some(Route::Exercise { lesson_path, exercise_path })
+        Some(Route::Exercise { course: course_path.clone(), lesson_path, exercise_path })
     }).unwrap_or(None)).unwrap_or(None);
 
     content_from_toolbar(
@@ -377,7 +347,7 @@ pub(crate) fn html_page(lessons: Lessons, lesson_path: String, exercise_path: St
             <Toolbar name={lesson_name} return_route={return_route} prev_route={prev_route} next_route={next_route}/>
                 },
         html! {
-            <ExerciseComponent lesson_path={Option::<String>::None} exercise={exercise}/>
+            <ExerciseComponent course_path={course_path.clone()} reference={course.reference.clone()} lesson_path={Option::<String>::None} category_mode={course.category_mode(&exercise)} exercise={exercise}/>
         }
     )
 }
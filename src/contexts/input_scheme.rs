@@ -0,0 +1,224 @@
+use std::ops::Deref;
+use std::slice::Iter;
+use std::str::FromStr;
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use stylist::yew::styled_component;
+use web_sys::HtmlInputElement;
+use yew::html::ImplicitClone;
+use yew::prelude::*;
+
+use pali_course_core::model::TableLayout;
+
+use crate::ProviderProps;
+use crate::contexts::{ThemeContext, use_theme};
+
+const INPUT_SCHEME_KEY: &str = "input-scheme";
+
+/**
+ * How the accented Pāli letters can be typed with a plain keyboard.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputScheme {
+    Shorthand,
+    Velthuis,
+    HarvardKyoto,
+}
+
+impl ImplicitClone for InputScheme {}
+
+impl InputScheme {
+
+    pub(crate) fn iterator() -> Iter<'static, InputScheme> {
+        static INPUT_SCHEMES: [InputScheme; 3] = [InputScheme::Shorthand, InputScheme::Velthuis, InputScheme::HarvardKyoto];
+        INPUT_SCHEMES.iter()
+    }
+
+    /// (typed, Pāli)
+    pub fn mappings(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            InputScheme::Shorthand => &[
+                ("aa", "ā"),
+                ("ii", "ī"),
+                ("uu", "ū"),
+                (".t", "ṭ"),
+                (".d", "ḍ"),
+                ("`n", "ṅ"),
+                ("~n", "ñ"),
+                (".n", "ṇ"),
+                (".m", "ṃ"),
+                (".l", "ḷ"),
+            ],
+            InputScheme::Velthuis => &[
+                ("aa", "ā"),
+                ("ii", "ī"),
+                ("uu", "ū"),
+                (".t", "ṭ"),
+                (".d", "ḍ"),
+                ("\"n", "ṅ"),
+                ("~n", "ñ"),
+                (".n", "ṇ"),
+                (".m", "ṃ"),
+                (".l", "ḷ"),
+            ],
+            InputScheme::HarvardKyoto => &[
+                ("A", "ā"),
+                ("I", "ī"),
+                ("U", "ū"),
+                ("T", "ṭ"),
+                ("D", "ḍ"),
+                ("G", "ṅ"),
+                ("J", "ñ"),
+                ("N", "ṇ"),
+                ("M", "ṃ"),
+                ("L", "ḷ"),
+            ],
+        }
+    }
+
+    pub fn convert(&self, mut typed: String) -> String {
+        for (from, to) in self.mappings().iter().chain(IAST_ALIASES.iter()) {
+            typed = typed.replace(from, to);
+        }
+        typed
+    }
+
+    /**
+     * Converts only what was just typed: "bhavaa" -> Some("bhavā"), "bhavā" -> None
+     */
+    pub fn convert_suffix(&self, typed: &str) -> Option<String> {
+        self.mappings().iter().chain(IAST_ALIASES.iter())
+            .find(|(from, _)| typed.ends_with(from))
+            .map(|(from, to)| format!("{}{}", &typed[..typed.len() - from.len()], to))
+    }
+
+    pub fn to_proper_string(&self) -> String {
+        match self {
+            InputScheme::Shorthand => "Shorthand",
+            InputScheme::Velthuis => "Velthuis",
+            InputScheme::HarvardKyoto => "Harvard-Kyoto",
+        }.to_string()
+    }
+
+    /// the "Input Method" tutorial table
+    pub fn table_layout(&self) -> TableLayout {
+        TableLayout::new(self.mappings().iter()
+            .map(|(from, to)| vec![from.to_string(), to.to_string()])
+            .collect())
+    }
+
+}
+
+/// other ways of writing the same letters that come from pasting IAST / ISO 15919 text
+const IAST_ALIASES: [(&str, &str); 8] = [
+    ("ṁ", "ṃ"),
+    ("Ṁ", "Ṃ"),
+    ("ŋ", "ṃ"), // as some Pāli texts write it
+    ("Ŋ", "Ṃ"),
+    ("ē", "e"), // ISO 15919 marks long e and o, which Pāli writes plain
+    ("Ē", "E"),
+    ("ō", "o"),
+    ("Ō", "O"),
+];
+
+impl FromStr for InputScheme {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Shorthand" =>    Ok(InputScheme::Shorthand),
+            "Velthuis" =>     Ok(InputScheme::Velthuis),
+            "HarvardKyoto" => Ok(InputScheme::HarvardKyoto),
+            _ =>              Err(()),
+        }
+    }
+}
+
+impl ToString for InputScheme {
+    fn to_string(&self) -> String {
+        match self {
+            InputScheme::Shorthand =>    "Shorthand",
+            InputScheme::Velthuis =>     "Velthuis",
+            InputScheme::HarvardKyoto => "HarvardKyoto",
+        }.to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct InputSchemeContext {
+    inner: UseStateHandle<InputScheme>,
+}
+
+impl InputSchemeContext {
+    pub fn new(inner: UseStateHandle<InputScheme>) -> Self {
+        Self { inner }
+    }
+
+    pub fn set(&self, scheme: InputScheme) {
+        let _ = LocalStorage::set(INPUT_SCHEME_KEY, &scheme);
+        self.inner.set(scheme)
+    }
+
+    pub fn scheme(&self) -> InputScheme {
+        (*self.inner).clone()
+    }
+}
+
+impl Deref for InputSchemeContext {
+    type Target = InputScheme;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.inner
+    }
+}
+
+impl PartialEq for InputSchemeContext {
+    fn eq(&self, rhs: &Self) -> bool {
+        *self.inner == *rhs.inner
+    }
+}
+
+#[styled_component(InputSchemeProvider)]
+pub(crate) fn input_scheme_provider(props: &ProviderProps) -> Html {
+    let input_scheme_context = InputSchemeContext::new(use_state(||
+        LocalStorage::get(INPUT_SCHEME_KEY).unwrap_or(InputScheme::Shorthand)
+    ));
+
+    html! {
+        <ContextProvider<InputSchemeContext> context={input_scheme_context}>
+            {props.children.clone()}
+        </ContextProvider<InputSchemeContext>>
+    }
+}
+
+#[hook]
+pub(crate) fn use_input_scheme() -> InputSchemeContext {
+    use_context::<InputSchemeContext>().unwrap()
+}
+
+/**
+ * Drop down for choosing the input scheme. The choice is remembered.
+ */
+#[styled_component(InputSchemeSwitcher)]
+pub fn input_scheme_switcher() -> Html {
+    let input_scheme: InputSchemeContext = use_input_scheme();
+    let theme: ThemeContext = use_theme();
+    let current = input_scheme.scheme();
+    let select_class = classes!("clickable", theme.kind().css_class_themed("side-options"), theme.kind().css_class_themed("select"));
+
+    let scheme_switcher = Callback::from(move |e: Event| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        if let Ok(scheme) = InputScheme::from_str(input.value().as_str()) {
+            input_scheme.set(scheme);
+        }
+    });
+
+    html! {
+        <select class={select_class} onchange={scheme_switcher}>
+            { for InputScheme::iterator().map(|scheme| html! {
+                <option value={scheme.to_string()} selected={*scheme == current}> { scheme.to_proper_string() } </option>
+            }) }
+        </select>
+    }
+}
@@ -1,19 +1,25 @@
 use gloo_net::Error;
 use gloo_net::http::{Request, Response};
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
 use stylist::yew::{Global, styled_component};
 use wasm_bindgen_futures::spawn_local;
 use yew::{Component, Context, Html, html};
 use yew::prelude::*;
 
-use crate::contexts::exercise::{Exercise, ExerciseComponent, ExerciseComponentProps};
+use pali_course_core::model::{BookReference, Course, CourseIndex, Courses, Exercise, ExerciseCategory};
+
+use crate::contexts::exercise::{ExerciseComponent, ExerciseComponentProps};
 use std::ops::Deref;
-use crate::{ProviderProps, get_lessons_json};
+use crate::{ProviderProps, get_lessons_json, get_lessons_url, log_str};
 use serde_wasm_bindgen::from_value;
 use crate::app::Route;
 
 #[derive(Properties, PartialEq)]
 pub struct ExercisesProps {
+    pub course_path: String,
+    pub reference: Option<BookReference>,
+    pub categories: Vec<ExerciseCategory>,
     pub lesson_path: Option<String>, // for anonymous lessons or single-page exercise
     pub exercises: Vec<Exercise>
 }
@@ -22,30 +28,26 @@ pub struct ExercisesProps {
 pub fn exercises(props: &ExercisesProps) -> Html {
     return html! {
         { for props.exercises.iter().map(|e| html! {
-            <ExerciseComponent lesson_path={props.lesson_path.clone()} exercise={e.clone()} />
+            <ExerciseComponent course_path={props.course_path.clone()} reference={props.reference.clone()} lesson_path={props.lesson_path.clone()}
+                category_mode={e.category_mode(&props.categories)} exercise={e.clone()} />
         }) }
     }
 }
 
-#[derive(PartialEq, Clone, Deserialize)]
-pub struct Lesson {
-    pub name: String,
-    pub path: String,
-    pub exercises: Vec<Exercise>
-}
-
-#[derive(PartialEq, Clone, Deserialize)]
-pub struct Lessons {
-    pub lessons: Vec<Lesson>
+#[derive(PartialEq, Clone)]
+pub enum LessonsState {
+    Loading,
+    Loaded(Courses),
+    Failed(String), // where the json went wrong
 }
 
 #[derive(Clone)]
 pub(crate) struct LessonsContext {
-    inner: UseStateHandle<Lessons>,
+    inner: UseStateHandle<LessonsState>,
 }
 
 impl Deref for LessonsContext {
-    type Target = Lessons;
+    type Target = LessonsState;
 
     fn deref(&self) -> &Self::Target {
         &*self.inner
@@ -59,15 +61,15 @@ impl PartialEq for LessonsContext {
 }
 
 impl LessonsContext {
-    pub fn new(inner: UseStateHandle<Lessons>) -> Self {
+    pub fn new(inner: UseStateHandle<LessonsState>) -> Self {
         Self { inner }
     }
 
-    pub fn set(&self, lessons: Lessons) {
+    pub fn set(&self, lessons: LessonsState) {
         self.inner.set(lessons)
     }
 
-    pub fn get_lessons(&self) -> Lessons {
+    pub fn state(&self) -> LessonsState {
         (*self.inner).clone()
     }
 
@@ -110,7 +112,17 @@ pub(crate) fn use_lessons() -> LessonsContext {
 
 #[styled_component(LessonsProvider)]
 pub(crate) fn theme_provider(props: &ProviderProps) -> Html {
-    let lessons_context = LessonsContext::new(use_state_eq(|| from_value::<Lessons>(get_lessons_json()).expect("couldn't load json")));
+    let lessons_context = LessonsContext::new(use_state_eq(|| LessonsState::Loading));
+
+    {
+        let lessons_context = lessons_context.clone();
+        use_effect_with_deps(move |_| {
+            spawn_local(async move {
+                lessons_context.set(load_lessons().await);
+            });
+            || ()
+        }, ());
+    }
 
     html! {
         <ContextProvider<LessonsContext> context={lessons_context}>
@@ -118,3 +130,57 @@ pub(crate) fn theme_provider(props: &ProviderProps) -> Html {
         </ContextProvider<LessonsContext>>
     }
 }
+
+enum LoadError {
+    Unavailable, // not deployed or offline, so the bundled lessons are used
+    Invalid(String),
+}
+
+/**
+ * The lessons at the configured url, or the bundled ones when those can't be fetched.
+ * Broken json is shown instead of being replaced so it gets noticed.
+ */
+async fn load_lessons() -> LessonsState {
+    let mut state = match fetch_lessons(get_lessons_url()).await {
+        Ok(courses) => LessonsState::Loaded(courses),
+        Err(LoadError::Invalid(message)) => LessonsState::Failed(message),
+        Err(LoadError::Unavailable) => {
+            match serde_path_to_error::deserialize(serde_wasm_bindgen::Deserializer::from(get_lessons_json())) {
+                Ok(courses) => LessonsState::Loaded(courses),
+                Err(err) => LessonsState::Failed(format!("bundled lessons at {}: {}", err.path(), err.inner())),
+            }
+        }
+    };
+    if let LessonsState::Loaded(courses) = &mut state {
+        courses.assign_ids();
+        courses.expand_paradigms();
+        courses.courses.iter().flat_map(|c: &Course| c.warnings()).for_each(|warning| log_str(warning.as_str()));
+    }
+    state
+}
+
+async fn fetch_lessons(index_url: String) -> Result<Courses, LoadError> {
+    let index: CourseIndex = fetch_json(&index_url).await?;
+    let base_url = &index_url[..index_url.rfind('/').map(|i| i + 1).unwrap_or(0)];
+    let mut courses = Vec::with_capacity(index.courses.len());
+    for entry in index.courses {
+        let mut lessons = Vec::with_capacity(entry.lessons.len());
+        for lesson_path in &entry.lessons {
+            lessons.push(fetch_json(&format!("{}{}/{}.json", base_url, entry.path, lesson_path)).await?);
+        }
+        courses.push(entry.into_course(lessons));
+    }
+    Ok(Courses { courses })
+}
+
+async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, LoadError> {
+    let response = Request::get(url).send().await.map_err(|_| LoadError::Unavailable)?;
+    // the dev server answers missing files with index.html
+    let is_json = response.headers().get("content-type").is_some_and(|t| t.contains("json"));
+    if !response.ok() || !is_json {
+        return Err(LoadError::Unavailable);
+    }
+    let text = response.text().await.map_err(|_| LoadError::Unavailable)?;
+    serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(text.as_str()))
+        .map_err(|err| LoadError::Invalid(format!("{} at {}: {}", url, err.path(), err.inner())))
+}
@@ -1,11 +1,16 @@
 #![allow(unused_imports)]
 
-pub(crate) use cell::{DEFAULT_SELECTION_STRING, DropDownCell, DropDownCellProps, SpoilerCell, SpoilerCellProps, TypeFieldCell};
-pub(crate) use exercise::{Exercise, ExerciseComponent, ExerciseComponentProps, ExerciseCategory, html_page};
-pub(crate) use lesson::{Exercises, Lesson, Lessons, LessonsContext, LessonsProvider, use_lessons};
-pub(crate) use table::{ExerciseMode, Table, TableLayout, TriSplit};
+pub(crate) use cell::{DEFAULT_SELECTION_STRING, DropDownCell, DropDownCellProps, LetterCell, MultipleChoiceCell, SpoilerCell, SpoilerCellProps, TypeFieldCell};
+pub(crate) use exercise::{ExerciseComponent, ExerciseComponentProps, html_page};
+pub(crate) use lesson::{Exercises, LessonsContext, LessonsProvider, LessonsState, use_lessons};
+pub(crate) use table::Table;
 pub(crate) use theme::{ThemeContext, ThemeKind, ThemeProvider, ThemeSwitcher, use_theme};
 pub(crate) use toolbar::Toolbar;
+pub(crate) use progress::TableProgress;
+pub(crate) use review::ReviewPage;
+pub(crate) use quiz::QuizPage;
+pub(crate) use script::{Script, ScriptContext, ScriptProvider, ScriptSwitcher, use_script};
+pub(crate) use input_scheme::{InputScheme, InputSchemeContext, InputSchemeProvider, InputSchemeSwitcher, use_input_scheme};
 
 mod theme;
 mod toolbar;
@@ -13,3 +18,8 @@ mod lesson;
 mod exercise;
 mod table;
 mod cell;
+mod progress;
+mod review;
+mod quiz;
+mod input_scheme;
+mod script;
@@ -0,0 +1,62 @@
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use pali_course_core::model::ExerciseMode;
+use pali_course_core::table::{Location, ParsedCell};
+
+/// bump when the stored format changes so old entries are dropped
+const PROGRESS_VERSION: u32 = 1;
+
+/**
+ * What's kept of a table between visits, stored under "progress-{id}" where the id is the exercise's id.
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct TableProgress {
+    pub version: u32,
+    pub shape: Vec<Vec<bool>>, // which cells are interactive; a lesson edit that changes this drops the progress
+    pub mode: Option<ExerciseMode>,
+    #[serde(default)]
+    pub prompt_col: Option<usize>, // quizzed from this column instead of as written
+    pub answers: Vec<(Location, String)>, // typed or selected
+    pub correct: usize,   // from the last check
+    pub incorrect: usize,
+}
+
+impl TableProgress {
+
+    pub fn new(parsed_table: &Vec<Vec<ParsedCell>>) -> Self {
+        Self {
+            version: PROGRESS_VERSION,
+            shape: table_shape(parsed_table),
+            ..Self::default()
+        }
+    }
+
+    /**
+     * None if nothing was saved or it was saved for a differently shaped table
+     */
+    pub fn load(id: &str, parsed_table: &Vec<Vec<ParsedCell>>) -> Option<Self> {
+        let key = storage_key(id);
+        let progress: TableProgress = LocalStorage::get(key.as_str()).ok()?;
+        if progress.version != PROGRESS_VERSION || progress.shape != table_shape(parsed_table) {
+            LocalStorage::delete(key.as_str());
+            return None;
+        }
+        Some(progress)
+    }
+
+    pub fn save(&self, id: &str) {
+        let _ = LocalStorage::set(storage_key(id).as_str(), self);
+    }
+
+}
+
+fn storage_key(id: &str) -> String {
+    format!("progress-{}", id)
+}
+
+fn table_shape(parsed_table: &Vec<Vec<ParsedCell>>) -> Vec<Vec<bool>> {
+    parsed_table.iter()
+        .map(|row| row.iter().map(|cell| cell.is_interactive()).collect())
+        .collect()
+}
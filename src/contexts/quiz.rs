@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use pali_course_core::model::{Course, ExerciseCategory, ExerciseMode, Lesson, TableLayout};
+use pali_course_core::quiz::{build_quiz, QuizConfig, QuizTable};
+use pali_course_core::review::ReviewRows;
+
+use crate::app::{content_titled, Route};
+use crate::contexts::{Table, use_input_scheme, use_script, use_theme};
+
+const DEFAULT_ROWS: usize = 10;
+
+/// ?from=1&to=5&categories=vocab,verbs&rows=20&mode=TypeField&seed=123 - the whole quiz is in the url so it can be sent to someone
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+struct QuizQuery {
+    from: Option<String>,
+    to: Option<String>,
+    categories: Option<String>, // comma separated ids
+    rows: Option<usize>,
+    mode: Option<ExerciseMode>,
+    seed: Option<u32>,
+}
+
+impl QuizQuery {
+    fn category_ids(&self) -> Vec<String> {
+        self.categories.iter()
+            .flat_map(|ids: &String| ids.split(','))
+            .filter(|id: &&str| !id.is_empty())
+            .map(|id: &str| id.to_string())
+            .collect()
+    }
+
+    fn with_category(&self, id: &str, included: bool) -> Self {
+        let mut ids: Vec<String> = self.category_ids().into_iter().filter(|i| i != id).collect();
+        if included {
+            ids.push(id.to_string());
+        }
+        Self { categories: Some(ids.join(",")), ..self.clone() }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct QuizPageProps {
+    pub course: Course,
+}
+
+/**
+ * Rows drawn from the tables of a range of lessons, one table for each kind of header
+ */
+#[function_component(QuizPage)]
+pub(crate) fn quiz_page(props: &QuizPageProps) -> Html {
+    let theme = use_theme();
+    let input_scheme = use_input_scheme();
+    let script = use_script();
+    let navigator = use_navigator();
+    let location = use_location();
+
+    let course = &props.course;
+    let query: QuizQuery = location.as_ref().and_then(|l| l.query::<QuizQuery>().ok()).unwrap_or_default();
+    let query_str = location.as_ref().map(|l| l.query_str().to_string()).unwrap_or_default();
+
+    let set_query = {
+        let course = course.path.clone();
+        Callback::from(move |query: QuizQuery| {
+            if let Some(navigator) = &navigator {
+                let _ = navigator.replace_with_query(&Route::Quiz { course: course.clone() }, &query);
+            }
+        })
+    };
+
+    // a link without a seed gets one, so it can be shared as it is
+    {
+        let set_query = set_query.clone();
+        let query = query.clone();
+        use_effect_with_deps(move |missing_seed| {
+            if *missing_seed {
+                set_query.emit(QuizQuery { seed: Some(rand::random()), ..query });
+            }
+            || ()
+        }, query.seed.is_none());
+    }
+
+    let on_select = |change: fn(&QuizQuery, String) -> QuizQuery| {
+        let set_query = set_query.clone();
+        let query = query.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            set_query.emit(change(&query, input.value()));
+        })
+    };
+    let on_from = on_select(|q, value| QuizQuery { from: Some(value), ..q.clone() });
+    let on_to = on_select(|q, value| QuizQuery { to: Some(value), ..q.clone() });
+    let on_rows = on_select(|q, value| QuizQuery { rows: value.parse().ok(), ..q.clone() });
+    let on_mode = on_select(|q, value| QuizQuery { mode: value.parse().ok(), ..q.clone() });
+    let new_rows = {
+        let set_query = set_query.clone();
+        let query = query.clone();
+        Callback::from(move |_: MouseEvent| set_query.emit(QuizQuery { seed: Some(rand::random()), ..query.clone() }))
+    };
+
+    let first_path = course.lessons.first().map(|l: &Lesson| l.path.clone());
+    let last_path = course.lessons.last().map(|l: &Lesson| l.path.clone());
+    let from = query.from.clone().or(first_path);
+    let to = query.to.clone().or(last_path);
+    let category_ids = query.category_ids();
+    let mode = query.mode.clone().map(|m: ExerciseMode| m.to_string()).unwrap_or_default();
+    let select_class = classes!("clickable", theme.kind.css_class_themed("select"));
+
+    let lesson_options = |selected: &Option<String>| html! {
+        { for course.lessons.iter().map(|lesson: &Lesson| html! {
+            <option value={lesson.path.clone()} selected={Some(&lesson.path) == selected.as_ref()}> { lesson.name.clone() } </option>
+        }) }
+    };
+    let category_boxes = course.categories.iter().map(|category: &ExerciseCategory| {
+        let set_query = set_query.clone();
+        let query = query.clone();
+        let id = category.id.clone();
+        let onchange = Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            set_query.emit(query.with_category(id.as_str(), input.checked()));
+        });
+        html! {
+            <label class="spaced"> <input type="checkbox" checked={category_ids.contains(&category.id)} onchange={onchange} /> { category.name.clone() } </label>
+        }
+    });
+
+    let tables: Vec<QuizTable> = match query.seed {
+        Some(seed) => build_quiz(course, &QuizConfig {
+            from: from.clone(),
+            to: to.clone(),
+            categories: category_ids.clone(),
+            rows: query.rows.unwrap_or(DEFAULT_ROWS),
+            seed: seed as u64,
+        }),
+        None => vec![],
+    };
+    let quiz_id = format!("quiz-{}-{}", course.path, query.seed.unwrap_or(0));
+
+    content_titled(String::from("Quiz"), Some(Route::Lessons { course: course.path.clone() }), html! { <>
+        <div class="info">
+            <div class="flexer">
+                <label class="spaced"> { "From " } <select class={select_class.clone()} onchange={on_from}> { lesson_options(&from) } </select> </label>
+                <label class="spaced"> { "through " } <select class={select_class.clone()} onchange={on_to}> { lesson_options(&to) } </select> </label>
+            </div>
+            <div class="flexer"> { for category_boxes } </div>
+            <div class="flexer">
+                <label class="spaced"> { "Rows " } <input type="number" min="1" value={query.rows.unwrap_or(DEFAULT_ROWS).to_string()} onchange={on_rows} /> </label>
+                <select class={select_class} onchange={on_mode}>
+                    <option value=""               selected={mode.is_empty()}>            {"Any mode"} </option>
+                    <option value="Show"           selected={mode == "Show"}>             {"Reveal all"} </option>
+                    <option value="HoverReveal"    selected={mode == "HoverReveal"}>      {"Hover reveal"} </option>
+                    <option value="ClickReveal"    selected={mode == "ClickReveal"}>      {"Click reveal"} </option>
+                    <option value="CensorByLetter" selected={mode == "CensorByLetter"}>   {"Reveal by letter"} </option>
+                    <option value="TypeField"      selected={mode == "TypeField"}>        {"Enter text"} </option>
+                    <option value="DropDown"       selected={mode == "DropDown"}>         {"Drop down"} </option>
+                    <option value="MultipleChoice" selected={mode == "MultipleChoice"}>   {"Multiple choice"} </option>
+                    <option value="Match"          selected={mode == "Match"}>            {"Match pairs"} </option>
+                    <option value="Flashcards"     selected={mode == "Flashcards"}>       {"Flashcards"} </option>
+                </select>
+                <button class="spaced" onclick={new_rows}> { "New rows" } </button>
+            </div>
+            <p> { "This page's address has the quiz in it, so sending it gives the same rows." } </p>
+        </div>
+        if tables.is_empty() {
+            <div class="flexer"><p class="info">{ "No tables in those lessons and categories." }</p></div>
+        }
+        { for tables.into_iter().enumerate().map(|(i, quiz_table)| {
+            let id = format!("{}-{}", quiz_id, i);
+            let table_layout = TableLayout { default_mode: query.mode.clone(), ..quiz_table.table_layout };
+            html! {
+                <div class="quiz-table">
+                    <div class="flexer"><p class="info">{ format!("from {} tables", quiz_table.sources.len()) }</p></div>
+                    <Table key={format!("{}-{}", id, query_str)} table_layout={table_layout} theme={theme.kind.clone()} category_mode={Option::<ExerciseMode>::None}
+                        id={id.clone()} review={Option::<ReviewRows>::None} input_scheme={input_scheme.scheme()} script={script.script()}/>
+                </div>
+            }
+        }) }
+    </> })
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use gloo_storage::{LocalStorage, Storage};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use pali_course_core::model::Courses;
+use pali_course_core::review::{CardKey, Recall, ReviewQueue, review_table};
+use pali_course_core::table::Location;
+
+use crate::app::{content_titled, Route};
+use crate::contexts::{Table, use_input_scheme, use_script, use_theme};
+use crate::today;
+
+const REVIEW_KEY: &str = "review";
+const REVIEW_LIMIT: usize = 50; // cards on the page at once
+
+pub fn load_queue() -> ReviewQueue {
+    LocalStorage::get(REVIEW_KEY).unwrap_or_default()
+}
+
+/// what a table saw of its cards, saved right away
+pub fn record_reviews(reviews: Vec<(CardKey, Recall)>) {
+    if reviews.is_empty() {
+        return;
+    }
+    let mut queue = load_queue();
+    let today = today() as i64;
+    for (key, recall) in reviews {
+        queue.record(key, recall, today);
+    }
+    let _ = LocalStorage::set(REVIEW_KEY, &queue);
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ReviewPageProps {
+    pub courses: Courses,
+}
+
+/**
+ * The due cards, one table per exercise they're from, in lesson order.
+ * Cards of exercises that were removed or edited away are left out.
+ */
+#[function_component(ReviewPage)]
+pub(crate) fn review_page(props: &ReviewPageProps) -> Html {
+    let theme = use_theme();
+    let input_scheme = use_input_scheme();
+    let script = use_script();
+
+    let queue = load_queue();
+    let due = queue.due(today() as i64);
+    let mut due_by_exercise: HashMap<&str, Vec<Location>> = HashMap::new();
+    for key in due.iter().take(REVIEW_LIMIT) {
+        due_by_exercise.entry(key.exercise.as_str()).or_default().push(key.location);
+    }
+
+    let tables: Vec<Html> = props.courses.courses.iter()
+        .flat_map(|course| course.lessons.iter().flat_map(move |lesson| lesson.exercises.iter().map(move |exercise| (course, lesson, exercise))))
+        .filter_map(|(course, lesson, exercise)| {
+            let locations = due_by_exercise.get(exercise.id.as_str())?;
+            let (table_layout, rows) = review_table(exercise.table_layout.as_ref()?, exercise.id.as_str(), locations)?;
+            let exercise_route = Route::Exercise { course: course.path.clone(), lesson_path: lesson.path.clone(), exercise_path: exercise.effective_path() };
+            let title = format!("{}: {}", lesson.name, exercise.title.clone().unwrap_or_else(|| exercise.effective_path()));
+            Some(html! {
+                <div class="review-exercise">
+                    <div class="flexer"> <h2> <Link<Route> to={exercise_route}> { title } </Link<Route>> </h2> </div>
+                    <Table key={exercise.id.clone()} table_layout={table_layout} theme={theme.kind.clone()} category_mode={course.category_mode(exercise)}
+                        id={format!("review-{}", exercise.id)} review={Some(rows)} input_scheme={input_scheme.scheme()} script={script.script()}/>
+                </div>
+            })
+        })
+        .collect();
+
+    let summary = format!("{} of {} cards due", due.len(), queue.len());
+    content_titled(String::from("Review"), Some(Route::Overview), html! { <>
+        <div class="flexer"><p class="info">{ summary }</p></div>
+        if tables.is_empty() {
+            <div class="flexer"><p class="info">{ "Nothing to review. Cells you reveal or check in the lessons come back here when they're due." }</p></div>
+        }
+        { for tables }
+    </> })
+}
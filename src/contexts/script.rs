@@ -0,0 +1,375 @@
+use std::ops::Deref;
+use std::slice::Iter;
+use std::str::FromStr;
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use stylist::yew::styled_component;
+use unicode_normalization::UnicodeNormalization;
+use web_sys::HtmlInputElement;
+use yew::html::ImplicitClone;
+use yew::prelude::*;
+
+use pali_course_core::table::TriSplit;
+
+use crate::ProviderProps;
+use crate::contexts::{ThemeContext, use_theme};
+
+const SCRIPT_KEY: &str = "script";
+
+/**
+ * The script Pāli columns are shown in. Answers are always typed and checked in Roman.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Script {
+    Roman,
+    Devanagari,
+    Sinhala,
+    Thai,
+    Myanmar,
+    Khmer,
+    Brahmi,
+}
+
+impl ImplicitClone for Script {}
+
+/// same order as ScriptLetters' arrays
+const VOWELS: [&str; 8] = ["a", "ā", "i", "ī", "u", "ū", "e", "o"];
+const CONSONANTS: [&str; 32] = [
+    "k", "kh", "g", "gh", "ṅ",
+    "c", "ch", "j", "jh", "ñ",
+    "ṭ", "ṭh", "ḍ", "ḍh", "ṇ",
+    "t", "th", "d", "dh", "n",
+    "p", "ph", "b", "bh", "m",
+    "y", "r", "l", "v", "s", "h", "ḷ",
+];
+const NIGGAHITA: &str = "ṃ";
+
+struct ScriptLetters {
+    vowels: [&'static str; 8], // at the start of a syllable
+    vowel_signs: [&'static str; 8], // after a consonant
+    consonants: [&'static str; 32],
+    virama: &'static str, // between consonants
+    final_virama: &'static str, // after the last one
+    niggahita: &'static str,
+    vowel_sign_first: bool, // Thai writes e and o before the consonant
+}
+
+static DEVANAGARI: ScriptLetters = ScriptLetters {
+    vowels: ["अ", "आ", "इ", "ई", "उ", "ऊ", "ए", "ओ"],
+    vowel_signs: ["", "ा", "ि", "ी", "ु", "ू", "े", "ो"],
+    consonants: ["क", "ख", "ग", "घ", "ङ", "च", "छ", "ज", "झ", "ञ", "ट", "ठ", "ड", "ढ", "ण", "त", "थ", "द", "ध", "न", "प", "फ", "ब", "भ", "म", "य", "र", "ल", "व", "स", "ह", "ळ"],
+    virama: "्",
+    final_virama: "्",
+    niggahita: "ं",
+    vowel_sign_first: false,
+};
+
+static SINHALA: ScriptLetters = ScriptLetters {
+    vowels: ["අ", "ආ", "ඉ", "ඊ", "උ", "ඌ", "එ", "ඔ"],
+    vowel_signs: ["", "ා", "ි", "ී", "ු", "ූ", "ෙ", "ො"],
+    consonants: ["ක", "ඛ", "ග", "ඝ", "ඞ", "ච", "ඡ", "ජ", "ඣ", "ඤ", "ට", "ඨ", "ඩ", "ඪ", "ණ", "ත", "ථ", "ද", "ධ", "න", "ප", "ඵ", "බ", "භ", "ම", "ය", "ර", "ල", "ව", "ස", "හ", "ළ"],
+    virama: "්",
+    final_virama: "්",
+    niggahita: "ං",
+    vowel_sign_first: false,
+};
+
+static THAI: ScriptLetters = ScriptLetters {
+    vowels: ["อ", "อา", "อิ", "อี", "อุ", "อู", "เอ", "โอ"],
+    vowel_signs: ["", "า", "ิ", "ี", "ุ", "ู", "เ", "โ"],
+    consonants: ["ก", "ข", "ค", "ฆ", "ง", "จ", "ฉ", "ช", "ฌ", "ญ", "ฏ", "ฐ", "ฑ", "ฒ", "ณ", "ต", "ถ", "ท", "ธ", "น", "ป", "ผ", "พ", "ภ", "ม", "ย", "ร", "ล", "ว", "ส", "ห", "ฬ"],
+    virama: "ฺ",
+    final_virama: "ฺ",
+    niggahita: "ํ",
+    vowel_sign_first: true,
+};
+
+static MYANMAR: ScriptLetters = ScriptLetters {
+    vowels: ["အ", "အာ", "ဣ", "ဤ", "ဥ", "ဦ", "ဧ", "ဩ"],
+    vowel_signs: ["", "ာ", "ိ", "ီ", "ု", "ူ", "ေ", "ော"],
+    consonants: ["က", "ခ", "ဂ", "ဃ", "င", "စ", "ဆ", "ဇ", "ဈ", "ဉ", "ဋ", "ဌ", "ဍ", "ဎ", "ဏ", "တ", "ထ", "ဒ", "ဓ", "န", "ပ", "ဖ", "ဗ", "ဘ", "မ", "ယ", "ရ", "လ", "ဝ", "သ", "ဟ", "ဠ"],
+    virama: "္",
+    final_virama: "်",
+    niggahita: "ံ",
+    vowel_sign_first: false,
+};
+
+static KHMER: ScriptLetters = ScriptLetters {
+    vowels: ["អ", "អា", "ឥ", "ឦ", "ឧ", "ឩ", "ឯ", "ឱ"],
+    vowel_signs: ["", "ា", "ិ", "ី", "ុ", "ូ", "េ", "ោ"],
+    consonants: ["ក", "ខ", "គ", "ឃ", "ង", "ច", "ឆ", "ជ", "ឈ", "ញ", "ដ", "ឋ", "ឌ", "ឍ", "ណ", "ត", "ថ", "ទ", "ធ", "ន", "ប", "ផ", "ព", "ភ", "ម", "យ", "រ", "ល", "វ", "ស", "ហ", "ឡ"],
+    virama: "្", // coeng, which writes the next consonant below
+    final_virama: "៑", // viriam, since coeng doesn't end a word
+    niggahita: "ំ",
+    vowel_sign_first: false,
+};
+
+static BRAHMI: ScriptLetters = ScriptLetters {
+    vowels: ["𑀅", "𑀆", "𑀇", "𑀈", "𑀉", "𑀊", "𑀏", "𑀑"],
+    vowel_signs: ["", "𑀸", "𑀺", "𑀻", "𑀼", "𑀽", "𑁂", "𑁄"],
+    consonants: ["𑀓", "𑀔", "𑀕", "𑀖", "𑀗", "𑀘", "𑀙", "𑀚", "𑀛", "𑀜", "𑀝", "𑀞", "𑀟", "𑀠", "𑀡", "𑀢", "𑀣", "𑀤", "𑀥", "𑀦", "𑀧", "𑀨", "𑀩", "𑀪", "𑀫", "𑀬", "𑀭", "𑀮", "𑀯", "𑀲", "𑀳", "𑀴"],
+    virama: "𑁆",
+    final_virama: "𑁆",
+    niggahita: "𑀁",
+    vowel_sign_first: false,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Letter {
+    Vowel(usize),
+    Consonant(usize),
+    Niggahita,
+}
+
+impl Script {
+
+    pub(crate) fn iterator() -> Iter<'static, Script> {
+        static SCRIPTS: [Script; 7] = [Script::Roman, Script::Devanagari, Script::Sinhala, Script::Thai, Script::Myanmar, Script::Khmer, Script::Brahmi];
+        SCRIPTS.iter()
+    }
+
+    fn letters(&self) -> Option<&'static ScriptLetters> {
+        match self {
+            Script::Roman => None,
+            Script::Devanagari => Some(&DEVANAGARI),
+            Script::Sinhala => Some(&SINHALA),
+            Script::Thai => Some(&THAI),
+            Script::Myanmar => Some(&MYANMAR),
+            Script::Khmer => Some(&KHMER),
+            Script::Brahmi => Some(&BRAHMI),
+        }
+    }
+
+    /**
+     * "bhavati" -> "भवति". Anything that isn't a Pāli letter is kept as it is.
+     */
+    pub fn transliterate(&self, roman: &str) -> String {
+        let letters = match self.letters() {
+            None => return roman.to_string(),
+            Some(letters) => letters,
+        };
+
+        let mut out = String::new();
+        let mut after_consonant = false;
+        let mut syllable_start: usize = 0; // where a Thai e/o goes
+        for token in tokenize(roman.nfc().collect::<String>().to_lowercase().as_str()) {
+            match token {
+                Ok(Letter::Consonant(index)) => {
+                    if after_consonant {
+                        out.push_str(letters.virama);
+                    } else {
+                        syllable_start = out.len();
+                    }
+                    out.push_str(letters.consonants[index]);
+                    after_consonant = true;
+                },
+                Ok(Letter::Vowel(index)) => {
+                    if !after_consonant {
+                        out.push_str(letters.vowels[index]);
+                    } else if letters.vowel_sign_first && (VOWELS[index] == "e" || VOWELS[index] == "o") {
+                        let last_consonant = out[syllable_start..].rfind(letters.virama)
+                            .map(|i| syllable_start + i + letters.virama.len())
+                            .unwrap_or(syllable_start);
+                        out.insert_str(last_consonant, letters.vowel_signs[index]);
+                    } else {
+                        out.push_str(letters.vowel_signs[index]);
+                    }
+                    after_consonant = false;
+                },
+                Ok(Letter::Niggahita) => {
+                    if after_consonant {
+                        out.push_str(letters.final_virama);
+                    }
+                    out.push_str(letters.niggahita);
+                    after_consonant = false;
+                },
+                Err(other) => {
+                    if after_consonant {
+                        out.push_str(letters.final_virama);
+                    }
+                    out.push(other);
+                    after_consonant = false;
+                },
+            }
+        }
+        if after_consonant {
+            out.push_str(letters.final_virama);
+        }
+        out
+    }
+
+    /**
+     * Keeps a consonant with the vowel it's written with: "bhav|ati|" -> "भ", "वति", ""
+     * The answers stay in Roman.
+     */
+    pub fn transliterate_split(&self, split: &TriSplit) -> TriSplit {
+        if self.letters().is_none() {
+            return split.clone();
+        }
+        let (start, middle) = shift_consonants(split.start.as_str(), split.middle.as_str());
+        let (middle, end) = shift_consonants(middle.as_str(), split.end.as_str());
+        TriSplit {
+            start: self.transliterate(start.as_str()),
+            middle: self.transliterate(middle.as_str()),
+            end: self.transliterate(end.as_str()),
+            answers: split.answers.clone(),
+        }
+    }
+
+    pub fn to_proper_string(&self) -> String {
+        match self {
+            Script::Roman => "Roman",
+            Script::Devanagari => "Devanagari",
+            Script::Sinhala => "Sinhala",
+            Script::Thai => "Thai",
+            Script::Myanmar => "Myanmar",
+            Script::Khmer => "Khmer",
+            Script::Brahmi => "Brahmi",
+        }.to_string()
+    }
+
+}
+
+fn tokenize(roman: &str) -> Vec<Result<Letter, char>> {
+    let chars: Vec<char> = roman.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let one = chars[i].to_string();
+        let two: Option<String> = chars.get(i + 1).filter(|c| **c == 'h').map(|h| format!("{}{}", one, h));
+        if let Some(index) = two.as_ref().and_then(|two| CONSONANTS.iter().position(|c| c == two)) {
+            tokens.push(Ok(Letter::Consonant(index)));
+            i += 2;
+            continue;
+        }
+        tokens.push(if let Some(index) = CONSONANTS.iter().position(|c| *c == one) {
+            Ok(Letter::Consonant(index))
+        } else if let Some(index) = VOWELS.iter().position(|v| *v == one) {
+            Ok(Letter::Vowel(index))
+        } else if one == NIGGAHITA {
+            Ok(Letter::Niggahita)
+        } else {
+            Err(chars[i])
+        });
+        i += 1;
+    }
+    tokens
+}
+
+/// moves the consonants ending `before` onto `after` when `after` goes on with a letter
+fn shift_consonants(before: &str, after: &str) -> (String, String) {
+    let continues = after.chars().next()
+        .map(|c| tokenize(c.to_lowercase().to_string().as_str())[0].is_ok())
+        .unwrap_or(false);
+    if !continues {
+        return (before.to_string(), after.to_string());
+    }
+    let split_at = before.char_indices().rev()
+        .take_while(|(_, c)| CONSONANTS.contains(&c.to_lowercase().to_string().as_str()))
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(before.len());
+    (before[..split_at].to_string(), format!("{}{}", &before[split_at..], after))
+}
+
+impl FromStr for Script {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Roman" =>      Ok(Script::Roman),
+            "Devanagari" => Ok(Script::Devanagari),
+            "Sinhala" =>    Ok(Script::Sinhala),
+            "Thai" =>       Ok(Script::Thai),
+            "Myanmar" =>    Ok(Script::Myanmar),
+            "Khmer" =>      Ok(Script::Khmer),
+            "Brahmi" =>     Ok(Script::Brahmi),
+            _ =>            Err(()),
+        }
+    }
+}
+
+impl ToString for Script {
+    fn to_string(&self) -> String {
+        self.to_proper_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ScriptContext {
+    inner: UseStateHandle<Script>,
+}
+
+impl ScriptContext {
+    pub fn new(inner: UseStateHandle<Script>) -> Self {
+        Self { inner }
+    }
+
+    pub fn set(&self, script: Script) {
+        let _ = LocalStorage::set(SCRIPT_KEY, &script);
+        self.inner.set(script)
+    }
+
+    pub fn script(&self) -> Script {
+        (*self.inner).clone()
+    }
+}
+
+impl Deref for ScriptContext {
+    type Target = Script;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.inner
+    }
+}
+
+impl PartialEq for ScriptContext {
+    fn eq(&self, rhs: &Self) -> bool {
+        *self.inner == *rhs.inner
+    }
+}
+
+#[styled_component(ScriptProvider)]
+pub(crate) fn script_provider(props: &ProviderProps) -> Html {
+    let script_context = ScriptContext::new(use_state(||
+        LocalStorage::get(SCRIPT_KEY).unwrap_or(Script::Roman)
+    ));
+
+    html! {
+        <ContextProvider<ScriptContext> context={script_context}>
+            {props.children.clone()}
+        </ContextProvider<ScriptContext>>
+    }
+}
+
+#[hook]
+pub(crate) fn use_script() -> ScriptContext {
+    use_context::<ScriptContext>().unwrap()
+}
+
+/**
+ * Drop down for choosing the script. The choice is remembered.
+ */
+#[styled_component(ScriptSwitcher)]
+pub fn script_switcher() -> Html {
+    let script: ScriptContext = use_script();
+    let theme: ThemeContext = use_theme();
+    let current = script.script();
+    let select_class = classes!("clickable", theme.kind().css_class_themed("side-options"), theme.kind().css_class_themed("select"));
+
+    let script_switcher = Callback::from(move |e: Event| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        if let Ok(chosen) = Script::from_str(input.value().as_str()) {
+            script.set(chosen);
+        }
+    });
+
+    html! {
+        <select class={select_class} onchange={script_switcher}>
+            { for Script::iterator().map(|option| html! {
+                <option value={option.to_string()} selected={*option == current}> { option.to_proper_string() } </option>
+            }) }
+        </select>
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::mem::discriminant;
 use std::ops::Deref;
 use std::panic;
@@ -18,10 +18,24 @@ use yew::{Callback, Html, html, Properties, use_state};
 use yew::prelude::*;
 use yew::props;
 use yew_router::prelude::*;
+use yew_router::scope_ext::LocationHandle;
 
 use crate::{get_lessons_json, log_dbg, log_display, log_str};
-use crate::app::empty_html;
-use crate::contexts::{DEFAULT_SELECTION_STRING, DropDownCell, Exercise, ExerciseComponent, ExerciseComponentProps, Exercises, Lesson, Lessons, SpoilerCell, TypeFieldCell, ThemeContext, ThemeKind, ThemeProvider, Toolbar, ExerciseCategory};
+use crate::app::{empty_html, Route};
+use crate::contexts::{DEFAULT_SELECTION_STRING, DropDownCell, LetterCell, MultipleChoiceCell, ExerciseComponent, ExerciseComponentProps, Exercises, SpoilerCell, TypeFieldCell, ThemeContext, ThemeKind, ThemeProvider, Toolbar};
+use pali_course_core::check::CheckStrictness;
+use pali_course_core::model::{ExerciseMode, TableLayout};
+use pali_course_core::options::{DropDownOptionsStyle, create_options_style};
+use pali_course_core::flashcards::{Deck, flashcard_rows};
+use pali_course_core::matching::{match_columns, match_pool};
+use pali_course_core::review::{Recall, ReviewRows};
+use pali_course_core::seed::{seeded_rng, table_seed};
+use pali_course_core::table::{GetLocation, Location, ParsedCell, ParsedTable, TriSplit, count_header_rows, create_location_table, create_parsed_table, prompt_with_column};
+
+use crate::contexts::cell::graphemes;
+use crate::contexts::review::record_reviews;
+use pali_course_core::check::check_answer;
+use crate::contexts::{InputScheme, Script, TableProgress};
 use crate::contexts::toolbar::TOOLBAR_HEIGHT;
 use crate::contexts::use_theme;
 use crate::html_if_some;
@@ -29,36 +43,63 @@ use crate::contexts::theme::Theme;
 use std::borrow::BorrowMut;
 use ExerciseMode::*;
 
-type DataTable = Vec<Vec<String>>;
-
-#[derive(Properties, PartialEq, Clone, Deserialize)]
-pub struct TableLayout {
-    // pub table: DataTable,
-    // pub initial_mode: ExerciseMode,
-    pub table: DataTable,
-    pub key_col: Option<usize>, // Show when rows unordered
-    pub shuffle_rows: Option<bool>,
-    pub default_mode: Option<ExerciseMode>, // Default: Censor
-    pub options_style_type: Option<OptionsStyleType>, // predicted
-}
-
 #[derive(Properties, PartialEq, Clone)]
 pub struct ThemedTableProps {
     pub theme: ThemeKind,
     pub table_layout: TableLayout,
-    pub categories: Vec<ExerciseCategory>,
+    pub category_mode: Option<ExerciseMode>, // from the exercise's categories
     pub id: String,
+    pub review: Option<ReviewRows>, // where its cells are review cards, None when they aren't kept
+    pub input_scheme: InputScheme,
+    pub script: Script,
 }
 
 
 pub(crate) struct Table {
-    pub parsed_table: ParsedTable,
+    pub base_table: ParsedTable, // as written
+    pub prompt_col: Option<usize>, // quizzed from this column instead of as written
+    pub parsed_table: ParsedTable, // as quizzed
     pub location_table: Vec<Vec<Location>>,
     pub input_tracking: Option<bool>,
     pub reset: bool,
     pub mode: ExerciseMode,
     pub options_style: DropDownOptionsStyle,
+    pub header_rows: usize,
     pub type_field_size: Vec<i32>, // by column
+    pub letters_revealed: HashMap<Location, usize>, // for CensorByLetter
+    pub revealed: HashSet<Location>, // for ClickReveal
+    pub seed: u32, // row order, shared through the url
+    pub answers: HashMap<Location, String>, // typed or selected, saved between visits
+    pub match_columns: Option<(usize, usize)>, // (key, pool) when the table can be matched
+    pub matching: MatchState,
+    pub deck: Deck, // for Flashcards
+    pub has_flashcards: bool, // whether a fresh deck would have any rows
+    pub flipped: bool,
+    pub last_check: (usize, usize), // (correct, incorrect)
+    _location_listener: Option<LocationHandle>,
+}
+
+/**
+ * For Match mode. Pairs are matched by putting the right answer in a cell, so they're kept with the other answers.
+ */
+#[derive(Default)]
+pub struct MatchState {
+    pub pool: Vec<Location>, // the pool column's cells, shuffled
+    pub picked: Option<Location>, // from the pool, waiting to be put in a cell
+    pub missed: Option<(Location, Location)>, // (cell, picked) of the last wrong pair
+}
+
+/// a row or column of the table, flipped all at once
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Line {
+    Row(usize),
+    Col(usize),
+}
+
+/// ?seed=123 - the same seed gives every table on the page the same row order
+#[derive(Serialize, Deserialize)]
+struct ShuffleQuery {
+    seed: u32,
 }
 
 #[derive(Clone, Debug)]
@@ -66,7 +107,19 @@ pub enum TableMsg {
     SwitchMode(ExerciseMode),
     CheckClicked,
     CellClicked(Location),
+    LetterRevealed(Location),
+    CellFlipped(Location),
+    LineFlipped(Line),
+    AnswerChanged(Location, String),
+    MatchPicked(Location),
+    MatchPlaced(Location),
+    PromptChanged(Option<usize>), // None for as written
+    FlashcardFlipped,
+    FlashcardAnswered(bool), // knew it
+    FlashcardsRedrilled,
     Reset,
+    Reshuffle,
+    SeedChanged(u32),
     Error,
 }
 
@@ -75,41 +128,89 @@ impl Component for Table {
     type Properties = ThemedTableProps;
 
     fn create(ctx: &Context<Self>) -> Self {
-        let parsed_table = create_parsed_table(&ctx.props().table_layout.table);
-        let location_table = create_location_table(&ctx.props().table_layout.table);
-        let options_summary = create_options_style(ctx.props().table_layout.options_style_type.clone(), &parsed_table, &location_table);
-        let interactive = parsed_table.iter().flat_map(|v| v).find(|c| c.is_interactive()).is_some();
-        let type_field_size = max_length(&parsed_table);
-        let mode = ctx.props().table_layout.default_mode.clone().unwrap_or(
+        let base_table = create_parsed_table(&ctx.props().table_layout.table);
+        let mut location_table = create_location_table(&ctx.props().table_layout.table);
+        let header_rows = count_header_rows(&base_table);
+
+        let seed = query_seed(ctx).unwrap_or_else(rand::random);
+        let location_listener = if ctx.props().table_layout.shuffle_rows.unwrap_or(false) {
+            shuffle_rows(&mut location_table, header_rows, table_seed(seed, &ctx.props().id));
+            ctx.link().add_location_listener(ctx.link().callback(|location: yew_router::history::Location|
+                location.query::<ShuffleQuery>().ok()
+                    .map(|query| TableMsg::SeedChanged(query.seed))
+                    .unwrap_or(TableMsg::Error)
+            ))
+        } else {
+            None
+        };
+        let interactive = base_table.iter().flat_map(|v| v).find(|c| c.is_interactive()).is_some();
+        let progress = TableProgress::load(ctx.props().id.as_str(), &base_table);
+        let width = base_table.iter().map(|row| row.len()).max().unwrap_or(0);
+        let prompt_col = progress.as_ref().and_then(|p: &TableProgress| p.prompt_col).filter(|col| *col < width);
+
+        let mut table = Self {
+            parsed_table: base_table.clone(),
+            base_table,
+            prompt_col: None,
+            location_table,
+            input_tracking: if interactive {Some(false)} else {None},
+            reset: false,
+            mode: Disabled,
+            options_style: DropDownOptionsStyle::Disabled,
+            header_rows,
+            type_field_size: vec![],
+            letters_revealed: HashMap::new(),
+            revealed: HashSet::new(),
+            seed,
+            answers: progress.as_ref().map(|p| p.answers.iter().cloned().collect()).unwrap_or_default(),
+            last_check: progress.as_ref().map(|p| (p.correct, p.incorrect)).unwrap_or((0, 0)),
+            match_columns: None,
+            matching: MatchState::default(),
+            deck: Deck::default(),
+            has_flashcards: false,
+            flipped: false,
+            _location_listener: location_listener,
+        };
+        table.apply_prompt(ctx, prompt_col);
+
+        let saved_mode = progress.as_ref()
+            .map(|p: &TableProgress| p.mode.clone()).flatten()
+            .filter(|m: &ExerciseMode| interactive && *m != Disabled && table.mode_available(m));
+        let default_mode = ctx.props().table_layout.default_mode.clone().filter(|m| table.mode_available(m));
+        table.mode = saved_mode.or(default_mode).unwrap_or(
             if interactive {
-                if ctx.props().categories.contains(&ExerciseCategory::Conjugation) {
-                    ExerciseMode::HoverReveal
-                } else { // Vocab, Verbs, other...
-                    ClickReveal
-                }
+                ctx.props().category_mode.clone().unwrap_or(ClickReveal)
             } else {
                 Disabled
             }
         );
+        table
+    }
 
-        Self {
-            parsed_table,
-            location_table,
-            input_tracking: if interactive {Some(false)} else {None},
-            reset: false,
-            mode,
-            options_style: options_summary,
-            type_field_size,
+    /// a page without a seed gets one, so its order can be shared as it is; the first table's is used by them all
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if !first_render || !ctx.props().table_layout.shuffle_rows.unwrap_or(false) {
+            return;
+        }
+        match query_seed(ctx) {
+            None => write_seed(ctx, self.seed),
+            Some(seed) if seed != self.seed => ctx.link().send_message(TableMsg::SeedChanged(seed)),
+            Some(_) => {},
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             TableMsg::SwitchMode(next_mode) => {
                 if self.mode == next_mode {
                     return false;
                 }
                 self.mode = next_mode;
+                self.revealed.clear();
+                if self.mode == Flashcards {
+                    self.restart_deck(ctx);
+                }
+                self.save(ctx);
                 true
             },
             TableMsg::CheckClicked => {
@@ -119,13 +220,145 @@ impl Component for Table {
                         self.input_tracking = Some(!prev.clone());
                     }
                 };
+                if self.is_checking() {
+                    let checked = self.checked_answers(ctx);
+                    let correct = checked.iter().filter(|(_, correct)| *correct).count();
+                    self.last_check = (correct, checked.len() - correct);
+                    self.save(ctx);
+                    self.record(ctx, checked.into_iter()
+                        .map(|(location, correct)| (location, if correct { Recall::Remembered } else { Recall::Forgot }))
+                        .collect());
+                }
+                true
+            },
+            TableMsg::AnswerChanged(location, answer) => {
+                if self.mode == MultipleChoice {
+                    if let Some(correct) = self.check_cell(&location, answer.as_str(), ctx) {
+                        self.record(ctx, vec![(location, if correct { Recall::Remembered } else { Recall::Forgot })]);
+                    }
+                }
+                self.answers.insert(location, answer);
+                self.save(ctx);
+                false
+            },
+            TableMsg::LetterRevealed(location) => {
+                let letter_count = match self.display_cell(&location, ctx) {
+                    ParsedCell::Interactive(text) => graphemes(text.middle.as_str()).len(),
+                    ParsedCell::Label(_) => 0,
+                };
+                let revealed = self.letters_revealed.entry(location).or_insert(0);
+                if *revealed >= letter_count {
+                    return false;
+                }
+                *revealed += 1;
+                true
+            },
+            TableMsg::CellFlipped(location) => {
+                if !self.revealed.remove(&location) {
+                    self.revealed.insert(location);
+                    self.record(ctx, vec![(location, Recall::Revealed)]);
+                }
+                true
+            },
+            TableMsg::LineFlipped(line) => {
+                let answers = self.line_answers(line);
+                if answers.iter().all(|location| self.revealed.contains(location)) {
+                    answers.iter().for_each(|location| { self.revealed.remove(location); });
+                } else {
+                    let newly_revealed: Vec<(Location, Recall)> = answers.into_iter()
+                        .filter(|location| self.revealed.insert(*location))
+                        .map(|location| (location, Recall::Revealed))
+                        .collect();
+                    self.record(ctx, newly_revealed);
+                }
+                true
+            },
+            TableMsg::MatchPicked(location) => {
+                if self.matching.picked == Some(location) {
+                    return false;
+                }
+                self.matching.picked = Some(location);
+                true
+            },
+            TableMsg::MatchPlaced(location) => {
+                let Some(picked) = self.matching.picked.take() else { return false };
+                let ParsedCell::Interactive(text) = self.parsed_table.get_location_unchecked(&picked) else { return false };
+                let value = text.middle.clone();
+                if self.check_cell(&location, value.as_str(), ctx) == Some(true) {
+                    self.answers.insert(location, value);
+                    self.matching.missed = None;
+                    self.save(ctx);
+                    self.record(ctx, vec![(location, Recall::Remembered)]);
+                } else {
+                    self.matching.missed = Some((location, picked));
+                    self.record(ctx, vec![(location, Recall::Forgot)]);
+                }
+                true
+            },
+            TableMsg::PromptChanged(prompt_col) => {
+                if self.prompt_col == prompt_col {
+                    return false;
+                }
+                self.apply_prompt(ctx, prompt_col);
+                self.answers.clear();
+                self.letters_revealed.clear();
+                self.revealed.clear();
+                self.reset = !self.reset;
+                if self.input_tracking.is_some() {
+                    self.input_tracking = Some(false);
+                }
+                if !self.mode_available(&self.mode) {
+                    self.mode = ClickReveal;
+                }
+                self.save(ctx);
+                true
+            },
+            TableMsg::FlashcardFlipped => {
+                self.flipped = !self.flipped;
+                true
+            },
+            TableMsg::FlashcardAnswered(knew) => {
+                let Some(row) = self.deck.current() else { return false };
+                let key_col = self.key_col(ctx).unwrap_or(0);
+                let recall = if knew { Recall::Remembered } else { Recall::Forgot };
+                self.record(ctx, self.parsed_table[row].iter().enumerate()
+                    .filter(|(col, cell)| *col != key_col && cell.is_interactive())
+                    .map(|(col, _)| ((row, col), recall))
+                    .collect());
+                self.deck.answer(knew);
+                self.flipped = false;
+                true
+            },
+            TableMsg::FlashcardsRedrilled => {
+                self.deck = self.deck.missed_deck();
+                self.flipped = false;
                 true
             },
             TableMsg::Reset => {
+                self.restart_deck(ctx);
                 self.reset = !self.reset;
+                self.letters_revealed.clear();
+                self.revealed.clear();
+                self.answers.clear();
+                self.matching.picked = None;
+                self.matching.missed = None;
                 if self.input_tracking.is_some() {
                     self.input_tracking = Some(false);
                 }
+                self.save(ctx);
+                true
+            }
+            TableMsg::Reshuffle => {
+                let seed: u32 = rand::random();
+                write_seed(ctx, seed);
+                self.reshuffle(ctx, seed);
+                true
+            }
+            TableMsg::SeedChanged(seed) => {
+                if self.seed == seed {
+                    return false;
+                }
+                self.reshuffle(ctx, seed);
                 true
             }
             TableMsg::CellClicked(_) => { false },
@@ -151,7 +384,14 @@ impl Component for Table {
         });
         let check_answers = ctx.link().callback(move |_: MouseEvent| TableMsg::CheckClicked);
         let reset = ctx.link().callback(move |_: MouseEvent| TableMsg::Reset);
+        let reshuffle = ctx.link().callback(move |_: MouseEvent| TableMsg::Reshuffle);
+        let shuffled = ctx.props().table_layout.shuffle_rows.unwrap_or(false);
         let disabled = self.mode == Disabled;
+        let prompt_switcher = ctx.link().callback(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            TableMsg::PromptChanged(input.value().parse().ok())
+        });
+        let prompt_select = self.prompt_select(prompt_switcher);
 
         let html = html! {
             <div class={table_area}>
@@ -162,18 +402,28 @@ impl Component for Table {
                 <div class="filler-right table-right">
                     if !disabled {
                         if self.mode.has_input() {
-                            <button class={classes!("check", side_options_class.clone(), "side-button", check_clicked_class.clone())} onclick={check_answers}> {"check"} </button>
+                            <button class={classes!("check", side_options_class.clone(), "side-button", check_clicked_class.clone())} onclick={check_answers} title={format!("last check: {} correct, {} incorrect", self.last_check.0, self.last_check.1)}> {"check"} </button>
                         }
                         <select class={classes!("options", "clickable", side_options_class.clone(), select_class)} value={self.mode.to_string().clone()} onchange={mode_switcher.clone()}>
                             <option value="Show"           selected={"Show" == self.mode.to_string().clone()}>            {"Reveal all"} </option>
                             <option value="HoverReveal"    selected={"HoverReveal" == self.mode.to_string().clone()}>     {"Hover reveal"} </option>
                             <option value="ClickReveal"    selected={"ClickReveal" == self.mode.to_string().clone()}>     {"Click reveal"} </option>
-                         // <option value="CensorByLetter" selected={"CensorByLetter" == self.mode.to_string().clone()}>  {"Reveal by letter"} </option>
-                            <option value="TypeField"      selected={"TypeField" == self.mode.to_string().clone()} disabled={self.options_style == DropDownOptionsStyle::Disabled}> {"Enter text"} </option>
-                            <option value="DropDown"       selected={"DropDown" == self.mode.to_string().clone()}  disabled={self.options_style == DropDownOptionsStyle::Disabled}> {"Drop down"} </option>
+                            <option value="CensorByLetter" selected={"CensorByLetter" == self.mode.to_string().clone()}>  {"Reveal by letter"} </option>
+                            <option value="TypeField"      selected={"TypeField" == self.mode.to_string().clone()} disabled={!self.mode_available(&TypeField)}> {"Enter text"} </option>
+                            <option value="DropDown"       selected={"DropDown" == self.mode.to_string().clone()}  disabled={!self.mode_available(&DropDown)}> {"Drop down"} </option>
+                            <option value="MultipleChoice" selected={"MultipleChoice" == self.mode.to_string().clone()} disabled={!self.mode_available(&MultipleChoice)}> {"Multiple choice"} </option>
+                            <option value="Match"          selected={"Match" == self.mode.to_string().clone()}     disabled={!self.mode_available(&Match)}> {"Match pairs"} </option>
+                            <option value="Flashcards"     selected={"Flashcards" == self.mode.to_string().clone()} disabled={!self.mode_available(&Flashcards)}> {"Flashcards"} </option>
                         </select>
+                        { prompt_select }
                         if self.mode.is_resettable() {
-                            <button class={classes!("reset", side_options_class, "side-button")} onclick={reset}> {"↺"} </button>
+                            <button class={classes!("reset", side_options_class.clone(), "side-button")} onclick={reset}> {"↺"} </button>
+                        }
+                        if shuffled {
+                            <button class={classes!("reshuffle", side_options_class, "side-button")} onclick={reshuffle} title="reshuffle rows"> {"⤮"} </button>
+                        }
+                        if self.mode == CensorByLetter {
+                            <span class="hints" title="letters revealed"> { format!("hints: {}", self.hints_used()) } </span>
                         }
                     }
                 </div>
@@ -189,6 +439,10 @@ impl Component for Table {
 
 impl Table {
 
+    fn mode_available(&self, mode: &ExerciseMode) -> bool {
+        mode.works_with(&self.options_style) && (*mode != Match || self.match_columns.is_some()) && (*mode != Flashcards || self.has_flashcards)
+    }
+
     fn is_checking(&self) -> bool {
         match &self.input_tracking {
             None => false,
@@ -200,9 +454,158 @@ impl Table {
         *self.input_tracking.as_ref().unwrap()
     }
 
+    pub fn hints_used(&self) -> usize {
+        self.letters_revealed.values().sum()
+    }
+
+    fn reshuffle(&mut self, ctx: &Context<Self>, seed: u32) {
+        self.seed = seed;
+        self.location_table = create_location_table(&ctx.props().table_layout.table);
+        shuffle_rows(&mut self.location_table, self.header_rows, table_seed(seed, &ctx.props().id));
+        self.restart_deck(ctx);
+        if let Some((_, pool_col)) = self.match_columns {
+            self.matching = MatchState { pool: match_pool(&self.parsed_table, pool_col, table_seed(seed, &ctx.props().id)), ..MatchState::default() };
+        }
+        self.reset = !self.reset;
+        self.letters_revealed.clear();
+        self.revealed.clear();
+        if self.input_tracking.is_some() {
+            self.input_tracking = Some(false);
+        }
+    }
+
+    /// the answers in a row or column
+    fn line_answers(&self, line: Line) -> Vec<Location> {
+        self.parsed_table.iter().enumerate().skip(self.header_rows)
+            .flat_map(|(row, cells)| cells.iter().enumerate().map(move |(col, cell)| ((row, col), cell)))
+            .filter(|((row, col), cell)| cell.is_interactive() && match line {
+                Line::Row(r) => *row == r,
+                Line::Col(c) => *col == c,
+            })
+            .map(|(location, _)| location)
+            .collect()
+    }
+
+    /// in ClickReveal, header labels flip their column and a row's first label flips its row
+    fn label_line(&self, location: &Location) -> Option<Line> {
+        if self.mode != ClickReveal {
+            return None;
+        }
+        let line = if location.0 < self.header_rows {
+            Line::Col(location.1)
+        } else if location.1 == 0 {
+            Line::Row(location.0)
+        } else {
+            return None;
+        };
+        (!self.line_answers(line).is_empty()).then_some(line)
+    }
+
+    /// which column to quiz from, labelled by its last header cell. Nothing for a single column
+    fn prompt_select(&self, onchange: Callback<Event>) -> Html {
+        let width = self.base_table.iter().map(|row| row.len()).max().unwrap_or(0);
+        if width < 2 {
+            return html! {};
+        }
+        let header = self.header_rows.checked_sub(1).and_then(|row| self.base_table.get(row));
+        let label = |col: usize| header
+            .and_then(|row| match row.get(col) {
+                Some(ParsedCell::Label(label)) if !label.trim().is_empty() => Some(label.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| format!("column {}", col + 1));
+        html! {
+            <select class={classes!("prompt", "clickable")} title="column to answer from" onchange={onchange}>
+                <option value="" selected={self.prompt_col.is_none()}> {"Prompt: as written"} </option>
+                { for (0..width).map(|col| html! {
+                    <option value={col.to_string()} selected={self.prompt_col == Some(col)}> { format!("Prompt: {}", label(col)) } </option>
+                }) }
+            </select>
+        }
+    }
+
+    /// the column shown for the others to be answered from
+    fn key_col(&self, ctx: &Context<Self>) -> Option<usize> {
+        self.prompt_col.or(ctx.props().table_layout.key_col)
+    }
+
+    /// the table as quizzed from the prompt column, and everything that depends on it
+    fn apply_prompt(&mut self, ctx: &Context<Self>, prompt_col: Option<usize>) {
+        self.prompt_col = prompt_col;
+        self.parsed_table = match prompt_col {
+            Some(col) => prompt_with_column(&self.base_table, col),
+            None => self.base_table.clone(),
+        };
+        if let Some(answer_check) = &ctx.props().table_layout.answer_check {
+            answer_check.apply(&mut self.parsed_table);
+        }
+        // written options don't fit the table from another column. Predictions go by the written row order
+        let options_style_type = if prompt_col.is_some() { None } else { ctx.props().table_layout.options_style_type.clone() };
+        let location_table = create_location_table(&ctx.props().table_layout.table);
+        self.options_style = create_options_style(options_style_type, &self.parsed_table, &location_table);
+        self.type_field_size = max_length(&self.parsed_table);
+        self.match_columns = match_columns(&self.parsed_table, &location_table, self.key_col(ctx));
+        self.matching = MatchState {
+            pool: self.match_columns.map(|(_, pool_col)| match_pool(&self.parsed_table, pool_col, table_seed(self.seed, &ctx.props().id))).unwrap_or_default(),
+            ..MatchState::default()
+        };
+        self.restart_deck(ctx);
+    }
+
+    fn restart_deck(&mut self, ctx: &Context<Self>) {
+        self.deck = Deck::new(flashcard_rows(&self.parsed_table, &self.location_table, self.key_col(ctx).unwrap_or(0)));
+        self.has_flashcards = !self.deck.rows.is_empty();
+        self.flipped = false;
+    }
+
+    fn save(&self, ctx: &Context<Self>) {
+        let mut progress = TableProgress::new(&self.base_table);
+        progress.mode = Some(self.mode.clone());
+        progress.prompt_col = self.prompt_col;
+        progress.answers = self.answers.iter()
+            .filter(|(_, answer)| !answer.is_empty())
+            .map(|(location, answer)| (location.clone(), answer.clone()))
+            .collect();
+        (progress.correct, progress.incorrect) = self.last_check;
+        progress.save(ctx.props().id.as_str());
+    }
+
+    /// whether each answered cell is correct
+    fn checked_answers(&self, ctx: &Context<Self>) -> Vec<(Location, bool)> {
+        let key_col = self.key_col(ctx);
+        self.answers.iter()
+            .filter(|(location, _)| Some(location.1) != key_col)
+            .filter_map(|(location, answer)| self.check_cell(location, answer.as_str(), ctx).map(|correct| (*location, correct)))
+            .collect()
+    }
+
+    fn check_cell(&self, location: &Location, answer: &str, ctx: &Context<Self>) -> Option<bool> {
+        let strictness = ctx.props().table_layout.check_strictness.clone().unwrap_or_default();
+        // typed answers the field didn't convert, as TypeFieldCell checks them
+        let scheme = self.cell_input_scheme(location, ctx).filter(|_| self.mode == TypeField);
+        let answer = scheme.map(|scheme| scheme.convert(answer.to_string())).unwrap_or(answer.to_string());
+        match self.parsed_table.get_location(location) {
+            Some(ParsedCell::Interactive(text)) => check_answer(answer.as_str(), &text.answers, &strictness),
+            _ => None,
+        }
+    }
+
+    /// only cells that are answered as written, since those are what the review page shows
+    fn record(&self, ctx: &Context<Self>, reviews: Vec<(Location, Recall)>) {
+        if let Some(review) = &ctx.props().review {
+            record_reviews(reviews.into_iter()
+                .filter(|(location, _)| self.base_table.get_location(location).is_some_and(|cell| cell.is_interactive()))
+                .filter_map(|(location, recall)| review.card(&location).map(|key| (key, recall)))
+                .collect());
+        }
+    }
+
     fn table_html(&self, ctx: &Context<Self>) -> Html {
+        if self.mode == Flashcards {
+            return self.flashcard_html(ctx);
+        }
         // let row_indices = (0..self.table.len());
-        return html! { // 'return' is required for some weird macro reason
+        return html! { <> // 'return' is required for some weird macro reason
             <table class="exercise-table"> {
                 for self.location_table.iter().map(|row_locations| { html! {
                     <tr> {
@@ -212,43 +615,215 @@ impl Table {
                     } </tr>
                 } })
             } </table>
+            if self.mode == Match {
+                { self.match_pool_html(ctx) }
+            }
+        </> }
+    }
+
+    /// the current row going down, with the header's labels beside it; the key column's cell is shown until it's flipped
+    fn flashcard_html(&self, ctx: &Context<Self>) -> Html {
+        let theme = &ctx.props().theme;
+        let Some(row) = self.deck.current() else {
+            let missed = self.deck.missed.len();
+            let redrill = ctx.link().callback(|_: MouseEvent| TableMsg::FlashcardsRedrilled);
+            let restart = ctx.link().callback(|_: MouseEvent| TableMsg::Reset);
+            return html! {
+                <div class="flashcard-area">
+                    <p> { format!("Knew {} of {}", self.deck.known(), self.deck.rows.len()) } </p>
+                    if missed > 0 {
+                        <button class="spaced" onclick={redrill}> { format!("Drill the {} missed", missed) } </button>
+                    }
+                    <button class="spaced" onclick={restart}> { "Start over" } </button>
+                </div>
+            };
+        };
+
+        let key_col = self.key_col(ctx).unwrap_or(0);
+        let labels = self.header_rows.checked_sub(1).map(|header_row| &self.parsed_table[header_row]);
+        let flip = ctx.link().callback(|_: MouseEvent| TableMsg::FlashcardFlipped);
+        let knew = ctx.link().callback(|_: MouseEvent| TableMsg::FlashcardAnswered(true));
+        let didnt = ctx.link().callback(|_: MouseEvent| TableMsg::FlashcardAnswered(false));
+
+        html! {
+            <div class="flashcard-area">
+                <p> { format!("{} / {}", self.deck.position + 1, self.deck.rows.len()) } </p>
+                <table class={classes!("exercise-table", "flashcard", "clickable")} onclick={flip.clone()}> {
+                    for (0..self.parsed_table[row].len()).map(|col| {
+                        let label = labels.and_then(|labels| match labels.get(col) {
+                            Some(ParsedCell::Label(label)) => Some(label.clone()),
+                            _ => None,
+                        });
+                        let cell = match self.display_cell(&(row, col), ctx) {
+                            ParsedCell::Label(value) => html! { { value } },
+                            ParsedCell::Interactive(text) if self.flipped || col == key_col => html! { <> { text.start } { text.middle } { text.end } </> },
+                            ParsedCell::Interactive(text) => html! { <> { text.start } <span class={theme.css_class_themed("spoiler")}> { text.middle } </span> { text.end } </> },
+                        };
+                        html! {
+                            <tr>
+                                if let Some(label) = label {
+                                    <td class={theme.css_class_themed("table-secondary")}> { label } </td>
+                                }
+                                <td> { cell } </td>
+                            </tr>
+                        }
+                    })
+                } </table>
+                if self.flipped {
+                    <button class="spaced" onclick={knew}> { "Knew it" } </button>
+                    <button class="spaced" onclick={didnt}> { "Didn't" } </button>
+                } else {
+                    <button class="spaced" onclick={flip}> { "Flip" } </button>
+                }
+            </div>
+        }
+    }
+
+    fn is_matched(&self, location: &Location, ctx: &Context<Self>) -> bool {
+        self.answers.get(location).and_then(|answer| self.check_cell(location, answer.as_str(), ctx)).unwrap_or(false)
+    }
+
+    /// what's left to match, picked with a click or dragged onto a cell
+    fn match_pool_html(&self, ctx: &Context<Self>) -> Html {
+        let select_class = ctx.props().theme.css_class_themed("select");
+        html! {
+            <div class="match-pool"> {
+                for self.matching.pool.iter()
+                    .filter(|location| !self.is_matched(location, ctx))
+                    .map(|location| {
+                        let ParsedCell::Interactive(text) = self.display_cell(location, ctx) else { return html! {} };
+                        let picked = location.clone();
+                        let onclick = ctx.link().callback(move |_: MouseEvent| TableMsg::MatchPicked(picked));
+                        let ondragstart = ctx.link().callback(move |e: DragEvent| {
+                            if let Some(data) = e.data_transfer() {
+                                let _ = data.set_data("text/plain", ""); // some browsers won't drag without data
+                            }
+                            TableMsg::MatchPicked(picked)
+                        });
+                        let chosen = (self.matching.picked == Some(*location)).then_some("chosen");
+                        html! {
+                            <button class={classes!(select_class.clone(), "choice", chosen)} draggable="true" onclick={onclick} ondragstart={ondragstart}>{ text.middle }</button>
+                        }
+                    })
+            } </div>
+        }
+    }
+
+    fn match_cell(&self, location: &Location, text: TriSplit, ctx: &Context<Self>) -> Html {
+        let theme = &ctx.props().theme;
+        if self.match_columns.is_some_and(|(key_col, _)| location.1 == key_col) {
+            return html! { <td> { text.start } { text.middle } { text.end } </td> };
+        }
+        if self.is_matched(location, ctx) {
+            return html! { <td class="correct_cell"> { text.start } { text.middle } { text.end } </td> };
+        }
+
+        let slot = location.clone();
+        let onclick = ctx.link().callback(move |_: MouseEvent| TableMsg::MatchPlaced(slot));
+        let ondragover = Callback::from(|e: DragEvent| e.prevent_default()); // allows dropping
+        let ondrop = ctx.link().callback(move |e: DragEvent| {
+            e.prevent_default();
+            TableMsg::MatchPlaced(slot)
+        });
+        let missed = self.matching.missed
+            .filter(|(cell, _)| cell == location)
+            .and_then(|(_, picked)| match self.display_cell(&picked, ctx) {
+                ParsedCell::Interactive(picked_text) => Some(picked_text.middle),
+                ParsedCell::Label(_) => None,
+            });
+        match missed {
+            Some(missed) => html! {
+                <td class={classes!("incorrect_cell", "clickable")} onclick={onclick} ondragover={ondragover} ondrop={ondrop}> { text.start } { missed } { text.end } </td>
+            },
+            None => html! {
+                <td class={classes!("match-slot", "clickable")} onclick={onclick} ondragover={ondragover} ondrop={ondrop}>
+                    { text.start } <span class={theme.css_class_themed("spoiler")}> { text.middle } </span> { text.end }
+                </td>
+            },
+        }
+    }
+
+    /// in one of the pali_cols, below the header rows; `unmarked` for tables without pali_cols
+    fn is_pali(&self, location: &Location, ctx: &Context<Self>, unmarked: bool) -> bool {
+        location.0 >= self.header_rows && ctx.props().table_layout.pali_cols.as_ref()
+            .map(|cols: &Vec<usize>| cols.contains(&location.1))
+            .unwrap_or(unmarked)
+    }
+
+    /// the script the cell is shown in, Roman unless it's in a Pāli column
+    fn cell_script(&self, location: &Location, ctx: &Context<Self>) -> Script {
+        if self.is_pali(location, ctx, false) { ctx.props().script.clone() } else { Script::Roman }
+    }
+
+    /// only Pāli is typed with the input scheme; "Time" would become "ṭime" in Harvard-Kyoto.
+    /// Tables that don't mark their pali_cols convert everything, as before they could
+    fn cell_input_scheme(&self, location: &Location, ctx: &Context<Self>) -> Option<InputScheme> {
+        self.is_pali(location, ctx, true).then(|| ctx.props().input_scheme.clone())
+    }
+
+    fn display_cell(&self, location: &Location, ctx: &Context<Self>) -> ParsedCell {
+        let script = self.cell_script(location, ctx);
+        match self.parsed_table.get_location_unchecked(location) {
+            ParsedCell::Label(val) => ParsedCell::Label(script.transliterate(val.as_str())),
+            ParsedCell::Interactive(text) => ParsedCell::Interactive(script.transliterate_split(text)),
         }
     }
 
     fn mediated_cell(&self, location: &Location, ctx: &Context<Self>) -> Html {
-        let cell: ParsedCell = (*self.parsed_table.get(location.0).unwrap().get(location.1).unwrap()).clone();
+        let cell: ParsedCell = self.display_cell(location, ctx);
         let theme = &ctx.props().theme;
         // let table_secondary_classes = theme.css_class_themed("table-secondary");
         let mut table_input = theme.css_class_themed("table-input");
 
         match cell {
-            ParsedCell::Label(val) => html! { <td> { val } </td> },
+            ParsedCell::Label(val) => match self.label_line(location) {
+                Some(line) => {
+                    let onclick = ctx.link().callback(move |_: MouseEvent| TableMsg::LineFlipped(line));
+                    let title = match line { Line::Row(_) => "reveal or hide the row", Line::Col(_) => "reveal or hide the column" };
+                    html! { <td class="clickable line-label" title={title} onmousedown={onclick}> { val } </td> }
+                },
+                None => html! { <td> { val } </td> },
+            },
             ParsedCell::Interactive(text) => {
                 return match self.mode.clone() {
                     Show => html! { <td class={theme.css_class_themed("interactive")}> { text.start }  { text.middle } { text.end } </td> },
                     HoverReveal => html! { <td class={theme.css_class_themed("spoilable")}> { text.start } <span class={theme.css_class_themed("spoiler")}> { text.middle } </span> { text.end } </td> },
-                    CensorByLetter => { empty_html() }
+                    CensorByLetter => {
+                        let location = location.clone();
+                        let revealed = self.letters_revealed.get(&location).copied().unwrap_or(0);
+                        let onreveal = ctx.link().callback(move |_| TableMsg::LetterRevealed(location));
+                        html! { <LetterCell text={text} theme={theme} class={theme.css_class_themed("interactive")} revealed={revealed} onreveal={onreveal} /> }
+                    },
 
-                    ClickReveal | TypeField | DropDown => {
+                    ClickReveal | TypeField | DropDown | MultipleChoice => {
 
                         // you could technically "hack" this with a clever enough key from json titles, but it'll just make two elements show the same thing
                         let key = format!("{}-{}-{},{}{}", ctx.props().id.clone(), self.mode.to_string(), location.0, location.1, self.reset);
 
                         match self.mode.clone() {
-                            ClickReveal => html! { <SpoilerCell text={text} theme={theme} class={theme.css_class_themed("spoilable")} key={key} /> },
-                            TypeField | DropDown => {
-                                if let Some(key_col) = ctx.props().table_layout.key_col {
+                            ClickReveal => {
+                                let location = location.clone();
+                                let onflip = ctx.link().callback(move |_| TableMsg::CellFlipped(location));
+                                let revealed = self.revealed.contains(&location);
+                                html! { <SpoilerCell text={text} theme={theme} class={theme.css_class_themed("spoilable")} revealed={revealed} onflip={onflip} key={key} /> }
+                            },
+                            TypeField | DropDown | MultipleChoice => {
+                                if let Some(key_col) = self.key_col(ctx) {
                                     if location.1 == key_col {
                                         return html! { <td> { text.start }  { text.middle } { text.end } </td> }
                                     }
                                 }
 
                                 let check_mode = self.is_checking_unwrap();
+                                let strictness = ctx.props().table_layout.check_strictness.clone().unwrap_or_default();
+                                let initial = self.answers.get(location).cloned().unwrap_or(DEFAULT_SELECTION_STRING);
+                                let answer_location = location.clone();
+                                let onchange = ctx.link().callback(move |answer: String| TableMsg::AnswerChanged(answer_location, answer));
 
                                 table_input.push_str(" type-field ");
                                 match self.mode.clone() {
                                     TypeField => {
-                                        html! { <TypeFieldCell text={text} class={table_input} check_mode={check_mode} size={self.type_field_size[location.1]} key={key} /> }
+                                        html! { <TypeFieldCell text={text} class={table_input} check_mode={check_mode} size={self.type_field_size[location.1]} scheme={self.cell_input_scheme(location, ctx)} strictness={strictness} initial={initial} onchange={onchange} key={key} /> }
                                     },
                                     DropDown => {
                                         let options = match self.options_style.clone() {
@@ -259,7 +834,16 @@ impl Table {
                                         let select_class = theme.css_class_themed("select");
                                         table_input.push_str(select_class.as_str());
 
-                                        html! { <DropDownCell text={text.clone()} class={table_input} location={location.clone()} options={options} check_mode={check_mode} key={key} /> }
+                                        let script = self.cell_script(location, ctx);
+                                        html! { <DropDownCell text={text.clone()} class={table_input} location={location.clone()} options={options} script={script} check_mode={check_mode} strictness={strictness} initial={initial} onchange={onchange} key={key} /> }
+                                    }
+                                    MultipleChoice => {
+                                        let options = match &self.options_style {
+                                            DropDownOptionsStyle::ByCol { col_options } => col_options.get(location.1).cloned().unwrap_or_default(),
+                                            _ => unreachable!("Accessed multiple choice without options by column"),
+                                        };
+                                        let script = self.cell_script(location, ctx);
+                                        html! { <MultipleChoiceCell text={text} class={theme.css_class_themed("select")} options={options} script={script} strictness={strictness} initial={initial} onchange={onchange} key={key} /> }
                                     }
                                     _ => unreachable!()
                                 }
@@ -267,6 +851,8 @@ impl Table {
                             _ => unreachable!()
                         }
                     },
+                    Match => self.match_cell(location, text, ctx),
+                    Flashcards => unreachable!("flashcards are shown a row at a time"),
                     Disabled => unreachable!("creating disabled exercise mode"),
                 }
             }
@@ -276,109 +862,23 @@ impl Table {
 
 }
 
-fn create_options_style(options_style_type: Option<OptionsStyleType>, parsed_table: &ParsedTable, location_table: &Vec<Vec<Location>>) -> DropDownOptionsStyle {
-    match predict_options_style_type(options_style_type, parsed_table, location_table) {
-        OptionsStyleType::Disabled => DropDownOptionsStyle::Disabled,
-        OptionsStyleType::All => {
-            let options = create_options(parsed_table.iter().flat_map(|row: &Vec<ParsedCell>| row).collect());
-            if options.len() > 1 { DropDownOptionsStyle::All { options } } else { DropDownOptionsStyle::Disabled }
-        },
-        OptionsStyleType::ByCol => {
-            let max_columns = parsed_table.iter().map(|row| row.len()).max().unwrap_or(0);
-            let mut col_options: Vec<Vec<&ParsedCell>> = vec![Vec::new(); max_columns];
-            for row in parsed_table {
-                for (col_index, cell) in row.iter().enumerate() {
-                    col_options[col_index].push(cell);
-                }
-            }
-            let col_options: Vec<Vec<String>> = col_options.iter().map(|col| create_options(col.iter().copied().collect())).collect();
-            DropDownOptionsStyle::ByCol { col_options }
-        }
-    }
-}
-
-fn predict_options_style_type(options_style_type: Option<OptionsStyleType>, parsed_table: &ParsedTable, location_table: &Vec<Vec<Location>>) -> OptionsStyleType {
-    if options_style_type.is_some() {
-        return options_style_type.unwrap();
-    }
-
-    if parsed_table.len() == 1 || parsed_table.iter().filter(|v| v.len() != 1 as usize).count() == 0 {
-        return OptionsStyleType::Disabled;
-    }
-
-    let top_left_opt: Option<&Location> = location_table.iter().flat_map(|v| v)
-        .find(|l: &&Location| parsed_table.get_location_unchecked(l).is_interactive());
-    if top_left_opt.is_none() {
-        return OptionsStyleType::Disabled; // no cells are interactive
-    }
-    let top_left = top_left_opt.unwrap();
-
-    let bottom_right: &Location = location_table.iter().flat_map(|v| v)
-        .rev()
-        .find(|l: &&Location| parsed_table.get_location_unchecked(l).is_interactive()).unwrap();
-
-    let extends_horizontally = top_left.1 == 0 && bottom_right.1 == parsed_table.get(bottom_right.0).unwrap().len() - 1;
-
-    if extends_horizontally {
-
-        let forms_a_grid = location_table.iter().flat_map(|v| v).find(|loc: &&Location| {
-            // let loc: Location = *loc_ref.clone();
-            if parsed_table.get_location_unchecked(loc).is_interactive()
-            { loc.left(top_left) || loc.above(top_left) || loc.right(bottom_right) || loc.below(bottom_right) }
-            else
-            { !loc.left(top_left) && !loc.above(top_left) && !loc.right(bottom_right) && !loc.below(bottom_right) }
-        }).is_none();
-
-        if forms_a_grid {
-            return OptionsStyleType::ByCol;
-        }
-    }
-    return OptionsStyleType::All;
-}
-
-fn create_parsed_table(table: &DataTable) -> ParsedTable {
-    table.clone().iter()
-        .map(|row: &Vec<String>| row.iter()
-            .map(|val: &String| split_bars(val.clone()))
-            .collect::<Vec<ParsedCell>>())
-        .collect()
+fn query_seed(ctx: &Context<Table>) -> Option<u32> {
+    ctx.link().location().and_then(|location| location.query::<ShuffleQuery>().ok()).map(|query| query.seed)
 }
 
-fn create_location_table(table: &DataTable) -> Vec<Vec<Location>> {
-    (0..table.len()).map(|row_index|
-        (0..table.get(row_index)
-            .map(|row: &Vec<String>| row.len())
-            .unwrap_or(0 as usize))
-        .map(|col_index| (row_index, col_index))
-        .collect())
-    .collect()
+/// sets ?seed= and keeps the rest of the query, like the quiz page's
+fn write_seed(ctx: &Context<Table>, seed: u32) {
+    let (Some(navigator), Some(route), Some(location)) = (ctx.link().navigator(), ctx.link().route::<Route>(), ctx.link().location()) else { return };
+    let mut query: HashMap<String, String> = location.query().unwrap_or_default();
+    query.insert("seed".to_string(), seed.to_string());
+    let _ = navigator.replace_with_query(&route, &query);
 }
 
-fn split_bars(str: String) -> ParsedCell {
-    // let find_fn: fn(&str, &str) -> Option<usize> = find; // compiler bug
-    let left: Option<usize> = str.as_str().find("|");
-    if left.is_some() {
-        let (start, middle) = str.split_at(left.unwrap());
-        let (_, middle) = middle.split_at(1);
-        let right: Option<usize> = middle.find("|");
-        if right.is_some() {
-            let (middle, end) = middle.split_at(right.unwrap());
-            let (_, end) = end.split_at(1);
-            return ParsedCell::Interactive(TriSplit::new(start.to_string(), middle.to_string(), end.to_string()));
-        }
+fn shuffle_rows(location_table: &mut Vec<Vec<Location>>, header_rows: usize, seed: u64) {
+    let mut rng = seeded_rng(seed);
+    if header_rows < location_table.len() {
+        location_table[header_rows..].shuffle(&mut rng);
     }
-    return ParsedCell::Label(str);
-}
-
-fn create_options(unfiltered_options: Vec<&ParsedCell>) -> Vec<String> {
-    let mut rng = rand::thread_rng();
-    let mut options: Vec<String> = unfiltered_options.iter()
-        .filter(|c: &&&ParsedCell| c.is_interactive())
-        .map(|c: &&ParsedCell| match c { ParsedCell::Interactive(text) => text.middle.clone(), _ => unreachable!() } )
-        .unique()
-        .collect();
-    options.shuffle(&mut rng);
-    options
 }
 
 fn max_length(table: &ParsedTable) -> Vec<i32> {
@@ -392,7 +892,11 @@ fn max_length(table: &ParsedTable) -> Vec<i32> {
     for row in table {
         for (col_index, cell) in row.iter().enumerate() {
             if let ParsedCell::Interactive(split) = cell {
-                let len = split.middle.len() as i32;
+                // sized for the longest thing that would be typed, not every alternative at once
+                let len = split.answers.iter()
+                    .skip(if split.answers.len() > 1 { 1 } else { 0 })
+                    .map(|a: &String| a.chars().count())
+                    .max().unwrap_or(0) as i32;
                 if len > column_max_sizes[col_index] {
                     column_max_sizes[col_index] = len;
                 }
@@ -407,81 +911,10 @@ fn max_length(table: &ParsedTable) -> Vec<i32> {
     return column_max_sizes;
 }
 
-#[derive(PartialEq, Clone, Deserialize)]
-#[serde(tag = "type", content = "options")]
-/// for DropDown mode
-pub enum OptionsStyleType {
-    Disabled,
-    All,
-    ByCol,
-}
-
-#[derive(PartialEq, Clone, Deserialize)]
-#[serde(tag = "type", content = "options")]
-/// for DropDown mode
-pub enum DropDownOptionsStyle {
-    Disabled,
-    All { options: Vec<String> },
-    ByCol { col_options: Vec<Vec<String>> },
-}
-
-impl FromStr for OptionsStyleType {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Disabled" => Ok(OptionsStyleType::Disabled),
-            "All" =>      Ok(OptionsStyleType::All),
-            "ByCol" =>    Ok(OptionsStyleType::ByCol),
-            _ =>          Err(()),
-        }
-    }
-}
-
-type ParsedTable = Vec<Vec<ParsedCell>>;
-pub(crate) type Location = (usize, usize);
-
-trait DirectionalInclusive<T> {
-    fn left (&self, of: T) -> bool;
-    fn right(&self, of: T) -> bool;
-    fn above(&self, of: T) -> bool;
-    fn below(&self, of: T) -> bool;
-}
-
-impl DirectionalInclusive<&Location> for Location {
-    fn left (&self, of: &Location) -> bool {
-        self.1 < of.1
-    }
-    fn right(&self, of: &Location) -> bool {
-        self.1 > of.1
-    }
-    fn above(&self, of: &Location) -> bool {
-        self.0 < of.0
-    }
-    fn below(&self, of: &Location) -> bool {
-        self.0 > of.0
-    }
-}
-
 trait SetLocation<T> {
     fn set_location_unchecked(&mut self, location: &Location, value: &T);
 }
 
-trait GetLocation<T> {
-    fn get_location(&self, location: &Location) -> Option<&T>;
-    fn get_location_unchecked(&self, location: &Location) -> &T;
-}
-
-impl<T> GetLocation<T> for Vec<Vec<T>> {
-    fn get_location(&self, location: &Location) -> Option<&T> {
-        self.get(location.0).map(|v| v.get(location.1)).flatten()
-    }
-
-    fn get_location_unchecked(&self, location: &Location) -> &T {
-        self.get(location.0).unwrap().get(location.1).unwrap()
-    }
-}
-
 impl<T> SetLocation<T> for Vec<Vec<T>> {
     fn set_location_unchecked(&mut self, location: &Location, value: &T) {
         let row: &Vec<T> = self.get(location.0).as_mut().unwrap();
@@ -489,94 +922,3 @@ impl<T> SetLocation<T> for Vec<Vec<T>> {
     }
 }
 
-
-#[derive(PartialEq, Clone)]
-pub struct TriSplit {
-    pub start: String,
-    pub middle: String,
-    pub end: String,
-}
-
-impl TriSplit {
-    pub fn new(start: String, middle: String, end: String) -> Self {
-        Self { start, middle, end }
-    }
-}
-
-#[derive(PartialEq, Clone)]
-pub enum ParsedCell {
-    Label(String),
-    Interactive(TriSplit),
-}
-
-impl ParsedCell {
-    fn is_interactive(&self) -> bool {
-        match self {
-            ParsedCell::Interactive(_) => true,
-            _ => false
-        }
-    }
-}
-
-#[derive(PartialEq, Clone, Deserialize, Debug)]
-pub enum ExerciseMode {
-    Disabled,
-    Show, // ABC
-    HoverReveal, // [][][]
-    ClickReveal, // [] -> A
-    CensorByLetter, // A[][] -> AB[]
-    TypeField, // [Az ]
-    DropDown, // >ABC or >XYZ
-}
-
-impl ExerciseMode {
-
-    fn is_resettable(&self) -> bool {
-        match self {
-            ClickReveal
-            | TypeField
-            | DropDown => true,
-            _ => false,
-        }
-    }
-
-    fn has_input(&self) -> bool {
-        match self {
-            TypeField
-            | DropDown => true,
-            _ => false,
-        }
-    }
-
-}
-
-impl FromStr for ExerciseMode {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Show"           => Ok(Show),
-            "HoverReveal"    => Ok(HoverReveal),
-            "ClickReveal"    => Ok(ClickReveal),
-            "CensorByLetter" => Ok(CensorByLetter),
-            "TypeField"      => Ok(TypeField),
-            "DropDown"       => Ok(DropDown),
-            "Disabled"       => Ok(Disabled),
-            _ => Err(())
-        }
-    }
-}
-
-impl ToString for ExerciseMode {
-    fn to_string(&self) -> String {
-        match self {
-            Show =>           "Show",
-            HoverReveal =>    "HoverReveal",
-            ClickReveal =>    "ClickReveal",
-            CensorByLetter => "CensorByLetter",
-            TypeField =>      "TypeField",
-            DropDown =>       "DropDown",
-            Disabled =>       "Disabled",
-        }.to_string()
-    }
-}
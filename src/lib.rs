@@ -74,6 +74,12 @@ extern "C" {
 
     #[wasm_bindgen(js_name = get_lessons_json)]
     fn get_lessons_json() -> JsValue;
+
+    #[wasm_bindgen(js_name = get_lessons_url)]
+    fn get_lessons_url() -> String;
+
+    #[wasm_bindgen(js_name = today)]
+    fn today() -> i32;
 }
 
 #[derive(Debug, PartialEq, Properties)]
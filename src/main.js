@@ -31,19 +31,43 @@ export function prefersDarkScheme() {
 }
 
 export function get_lessons_json() {
-    return lessons;
+    return courses;
+}
+
+/**
+ * where the lesson index is fetched from, set with <meta name="lessons-url"> in index.html
+ */
+export function get_lessons_url() {
+    const meta = document.querySelector('meta[name="lessons-url"]');
+    return meta ? meta.content : "/lessons/index.json";
+}
+
+/**
+ * days since 1970 in local time, so review cards come due at midnight where the student is
+ */
+export function today() {
+    const now = new Date();
+    return Math.floor((now.getTime() - now.getTimezoneOffset() * 60_000) / 86_400_000);
 }
 
 // store everything in one js file because of how the entire project gets built (it doesn't like to work with another file's path)
+// These are only used when static/lessons/ can't be fetched. Run `npm run export-lessons` after editing them here,
+// or edit the json files there directly (no rebuild needed) and copy the change back.
 
 /**
  * Basic Guide
  *
+ * Every course has its own lessons and path in the url ("/warder/lesson/1"). Its "reference" is the book that
+ *  "page" numbers link into: the base_url gets (page + page_offset) and then the optional suffix appended.
+ * A course declares its "categories", and an exercise lists the ids of the ones it's in ("categories": ["vocab"]).
+ *  A category's "default_mode" is used by its tables that don't have their own. Unknown ids are logged as warnings.
  * names and titles MUST be unique
  *
  * Replicate the textbook as close as possible
  * Titles should be short and in Title Case
  * The |markers| don't have to be around the entire cell ("pi |su|kkha")
+ * Inside the |markers|, "/" and "," separate answers that are each accepted ("|says, speaks|", "|asmi/amhi|").
+ *   Write "\\/" or "\\," when it's one answer ("|goes away\\/from|")
  * spell Pāli with a capital and ā. Sentences start with capital unless it's Pāli. use the diacritics, only use ṃ (to match textbook)
  * The explanation should be something that would spoil the problem when revealed such as a grammar rule
  *   or some sort of exception
@@ -51,20 +75,53 @@ export function get_lessons_json() {
  * You can't insert HTML into the strings (not my choice)
  * every field is optional (this allows basic text in the middle of the page without even a table)
  *  (except table_layout must have a table)
+ * "generated" makes the table instead of table_layout. View pali-course-core/src/model.rs GeneratedTable for the types.
+ * "paradigm" works out a regular verb's table from its stem when there's no table_layout:
+ *  { "type": "Conjugation", "stem": "bhav-", "class": 1, "tense": "Optative" } (Present, Imperative, Optative, Future, Aorist).
+ *  The stem is without the class's vowel ("des-" for deseti, class 7). Irregular forms still have to be written out.
+ *  Nouns: { "type": "Declension", "stem": "puris-", "stem_class": "A", "gender": "Masculine" } with stem classes
+ *  A, Aa (ā), I, Ii (ī), U and Consonant (-ant), and an optional "cases": ["Nominative", "Accusative"] for only some rows.
+ *  The irregular nouns in pali-course-core/src/declension.rs IRREGULARS ("rājan", "pitar", ...) are given by that stem.
+ *  Syllable lengths: { "type": "Syllables", "words": ["bhavati", "attā"] } also writes the explanation when there isn't one.
+ *  Sandhi: { "type": "Sandhi", "pairs": [["na", "atthi"], ["taṃ", "ahaṃ"]] } is typed in combined, also with an explanation.
+ *  The rules are pali-course-core/src/sandhi.rs WORD_RULES and RULES, which the Resources page's chart is made from too.
+ * "pali_cols" lists the columns written in Pāli, which get shown in the reader's chosen script.
+ *  Leave out columns with English in them (even "I |ahaṃ|"). Header rows (before the first |marker|) are never converted.
  *
- * View exercise.rs Exercise struct to see the form of "default_mode"
- *  The possible default-mode values are the names of ExerciseMode's (Show, HoverReveal, ClickReveal, CensorByLetter, TypeField, DropDown)
- * and similarly for "options_style", the options are in table.rs OptionsStyle.
+ * View pali-course-core/src/model.rs Exercise struct to see the form of "default_mode"
+ *  The possible default-mode values are the names of ExerciseMode's (Show, HoverReveal, ClickReveal, CensorByLetter, TypeField, DropDown, MultipleChoice, Match, Flashcards)
+ * and similarly for "options_style", the options are in pali-course-core/src/options.rs OptionsStyleType.
  *  ...=(Disabled, All, ByCol).
  *  The options style details are predicted. Users can't change the options style.
+ * "check_strictness" is how closely typed answers must match: Exact (default), IgnoreCase, or IgnorePunctuation (also ignores case).
+ *  Diacritics typed either composed or decomposed, and extra spaces, are always accepted.
+ * "answer_check": "Sandhi" checks the last column as the first two combined by the sandhi rules instead of by what's written.
  * This is technically designed to include tables that aren't grid shaped - no guarantees (it creates rows from the right)
  *
  * Don't worry about indentation and whatever, I can easily reformat it. The most helpful thing if anything would be
  * just copying and reformatting the text over from the textbook.
  *
  */
-const lessons = // { "courses": [...]}
-{ "lessons": [
+const courses =
+{ "courses": [
+{
+    "name": "Introduction to Pali",
+    "path": "warder",
+    "reference": {
+        "name": "Warder",
+        "base_url": "https://archive.org/details/A.K.WarderPali/A.%20K.%20Warder%20Pali/page/n",
+        "page_offset": 13,
+        "suffix": "/mode/1up"
+    },
+    "categories": [
+        { "id": "conjugation", "name": "Conjugations", "description": "Verb endings by person and number.", "default_mode": "HoverReveal" },
+        { "id": "tam", "name": "Tense-Aspect-Mood", "description": "The endings that mark when and how an action happens." },
+        { "id": "verbs", "name": "Verbs", "description": "Verb roots and their meanings." },
+        { "id": "vocab", "name": "Vocab", "description": "Words from each lesson's vocabulary." },
+        { "id": "aorist", "name": "Aorist", "description": "The past tense forms." },
+        { "id": "declension", "name": "Declension", "description": "Noun and pronoun endings by case and number." }
+    ],
+    "lessons": [
 {
     "name": "Tutorial",
     "path": "tutorial",
@@ -82,6 +139,7 @@ const lessons = // { "courses": [...]}
                     ["|body|", "|kāya|"],
                     ["|time|", "|kāla|"],
                 ],
+                "pali_cols": [1],
                 "default_mode": "ClickReveal",
                 "key_col": 0,
             },
@@ -105,22 +163,14 @@ const lessons = // { "courses": [...]}
         {
             "title": "Input Method",
             "exercise_level": "Important",
-            "info": "Here are the alternate ways to type the accented Pāli characters on this site. The star means that this is an \"important\" exercise (some exercises are less useful details, while some are more valuable to learning Pāli)",
-            "table_layout": {
-                // match the const in cell.rs
-                "table": [
-                    ["aa", "ā"],
-                    ["ii", "ī"],
-                    ["uu", "ū"],
-                    [".t", "ṭ"],
-                    [".d", "ḍ"],
-                    ["`n", "ṅ"],
-                    ["~n", "ñ"],
-                    [".n", "ṇ"],
-                    [".m", "ṃ"],
-                    [".l", "ḷ"],
-                ]
-            }
+            "info": "Here are the alternate ways to type the accented Pāli characters on this site. Pick the scheme you're used to; it's remembered for every exercise. Letters are converted as you type them, and backspace right after puts back what you typed. The star means that this is an \"important\" exercise (some exercises are less useful details, while some are more valuable to learning Pāli)",
+            // the table comes from the chosen scheme in input_scheme.rs
+            "generated": { "type": "InputScheme" }
+        },
+        {
+            "title": "Script",
+            "info": "Pāli can be shown in the script you read it in. Answers are still typed in Roman letters, as above.",
+            "generated": { "type": "Script" }
         },
         {
             "title": "Finally,",
@@ -157,10 +207,16 @@ const lessons = // { "courses": [...]}
                      * lc -> long, long
                      */
                 ],
+                "pali_cols": [0],
                 "default_mode": "DropDown",
             },
             "explanation": "e and o are long. Vowels are short if before double asp./unasp. consonant or ṃ. mh isn't a consonant.",
             "page": 4,
+        },
+        {
+            "title": "Long or Short Words",
+            "info": "The same for every syllable of whole words.",
+            "paradigm": { "type": "Syllables", "words": ["bhavati", "bhikkhu", "attā", "dhammo", "deseti", "amhākaṃ"] },
         }
     ]
 },
@@ -170,7 +226,7 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "√bhū Conjugation",
-            "categories": ["Conjugation"],
+            "categories": ["conjugation"],
             "info": "The conjugations for a 1st conjugation verb, \"to be\"",
             "exercise_level": "Important",
             "table_layout": {
@@ -180,11 +236,12 @@ const lessons = // { "courses": [...]}
                     ["2nd",    "bhav|asi|", "bhav|atha|"],
                     ["1st",    "bhav|āmi|", "bhav|āma|"],
                 ],
+                "pali_cols": [1, 2],
             }
         },
         {
             "title": "Verbs",
-            "categories": ["Verbs"],
+            "categories": ["verbs"],
             "info": "These are first conjugation verbs in the 3rd person singular.",
             "exercise_level": "Important",
             "table_layout": {
@@ -205,13 +262,14 @@ const lessons = // { "courses": [...]}
                     ["|har|",   "|āharati|",      "|brings|"],
                     ["|hū|",    "|hoti|",         "|is|"]
                 ],
+                "pali_cols": [0, 1],
                 "key_col": 1,
             },
             "page": 11,
         },
         {
             "title": "Vocab",
-            "categories": ["Vocab"],
+            "categories": ["vocab"],
             "info": "Masculine nouns in -a in the nominative singular.",
             "exercise_level": "Important",
             "table_layout": {
@@ -234,6 +292,7 @@ const lessons = // { "courses": [...]}
                     ["|samaṇo|",    "|ascetic, wanderer|"],
                     ["|samayo|",    "|time, occasion|"]
                 ],
+                "pali_cols": [0],
                 "key_col": 0,
                 "shuffle_rows": true,
             },
@@ -247,7 +306,7 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "Verbs",
-            "categories": ["Verbs"],
+            "categories": ["verbs"],
             "info": "These have irregular stems.",
             "exercise_level": "Important",
             "table_layout": {
@@ -266,6 +325,7 @@ const lessons = // { "courses": [...]}
                     ["|i|",      "|eti|",       "|goes|"],
                     ["|i|",      "|upeti|",     "|goes|"]
                 ],
+                "pali_cols": [0, 1],
                 "key_col": 1,
             },
             "explanation": "Consonants will become \"assimilated\" with each other like s + ch -> cch",
@@ -281,12 +341,13 @@ const lessons = // { "courses": [...]}
                     ["nom.", "-|o|",  "-|ā|"],
                     ["acc.", "-|aṃ|", "-|e|"],
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 17,
         },
         {
             "title": "Vocab",
-            "categories": ["Vocab"],
+            "categories": ["vocab"],
             // "info": "Masculine nouns in -a in the nominative singular.",
             "exercise_level": "Important",
             "table_layout": {
@@ -304,6 +365,7 @@ const lessons = // { "courses": [...]}
                     ["|saddo|",  "|noise, report|"],
                     ["|sugato|", "|well-gone|"]
                 ],
+                "pali_cols": [0],
                 "key_col": 0,
             }
         },
@@ -315,7 +377,7 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "bhagav- Declension",
-            "categories": ["Declension"],
+            "categories": ["declension"],
             "info": "Declension that also applies to brahman and rājan.",
             "exercise_level": "Important",
             "table_layout": {
@@ -327,12 +389,13 @@ const lessons = // { "courses": [...]}
                     ["nom. plur.",  "bhagav|ato|"  ],
                     ["acc. plur.",  "bhagav|ato|"  ],
                 ],
+                "pali_cols": [1],
             },
             "page": 20,
         },
         {
             "title": "√dis Conjugation",
-            "categories": ["Conjugation"],
+            "categories": ["conjugation"],
             "info": "The conjugations for a 7th conjugation verb, \"to teach\"",
             "exercise_level": "Important",
             "table_layout": {
@@ -342,13 +405,14 @@ const lessons = // { "courses": [...]}
                     ["2nd",    "dese|si|", "dese|tha|" ],
                     ["1st",    "dese|mi|", "dese|ma|"  ],
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 21,
         },
 
         {
             "title": "Verbs",
-            "categories": ["Verbs"],
+            "categories": ["verbs"],
             "info": "7th conjugation verbs.",
             "exercise_level": "Important",
             "table_layout": {
@@ -367,13 +431,14 @@ const lessons = // { "courses": [...]}
                     ["|vid|",    "|paṭisaṃvedeti|", "|feels, experiences|"],
                     ["|veṭh|",	 "|ibbeṭheti|",	    "|untwists, unravels, explains, rebuts|"]
                 ],
+                "pali_cols": [0, 1],
                 "key_col": 1,
             },
             "page": 21,
         },
         {
             "title": "Vocab",
-            "categories": ["Vocab"],
+            "categories": ["vocab"],
             "exercise_level": "Important",
             "table_layout": {
                 "table": [
@@ -385,6 +450,7 @@ const lessons = // { "courses": [...]}
                     ["|vihāro|",    "|life, dwelling|"],
                     ["|hattho|",    "|hand|"]
                 ],
+                "pali_cols": [0],
                 "key_col": 0,
             },
             "page": 22,
@@ -397,7 +463,7 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "First Aorist Form Conjugation",
-            "categories": ["Aorist"],
+            "categories": ["aorist"],
             "info": "For upasaṃkamati - approaches ➜ approached",
             "exercise_level": "Important",
             "table_layout": {
@@ -407,12 +473,13 @@ const lessons = // { "courses": [...]}
                     ["2nd", "upasaṃkam|i|", "upasaṃkam|ittha|"],
                     ["1st", "upasaṃkam|iṃ|", "upasaṃkam|imha/imhā|"]
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 24,
         },
         {
             "title": "Second Aorist Form Conjugation",
-            "categories": ["Aorist"],
+            "categories": ["aorist"],
             "info": "For deseti - teaches ➜ taught",
             "exercise_level": "Important",
             "table_layout": {
@@ -422,12 +489,13 @@ const lessons = // { "courses": [...]}
                     ["2nd", "des|esi|", "des|ittha|"],
                     ["1st", "des|esiṃ|", "des|imha|"]
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 25,
         },
         {
             "title": "Third Aorist Form Conjugation",
-            "categories": ["Aorist"],
+            "categories": ["aorist"],
             "info": "For karoti - makes/does/works ➜ made/did/worked",
             "exercise_level": "Important",
             "table_layout": {
@@ -437,6 +505,7 @@ const lessons = // { "courses": [...]}
                     ["2nd", "ak|āsi|", "ak|attha|"],
                     ["1st", "ak|āsiṃ|", "ak|amhā|"]
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 25,
         },
@@ -451,7 +520,7 @@ const lessons = // { "courses": [...]}
         // },
         {
             "title": "√hū Aorist Conjugation",
-            "categories": ["Aorist"],
+            "categories": ["aorist"],
             "info": "For hoti - is ➜ was",
             "exercise_level": "Important",
             "table_layout": {
@@ -461,12 +530,13 @@ const lessons = // { "courses": [...]}
                     ["2nd", "ah|osi|", "ah|uvattha|"],
                     ["1st", "ah|osiṃ|", "ah|umha|"]
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 26,
         },
         {
             "title": "Vocab",
-            "categories": ["Vocab"],
+            "categories": ["vocab"],
             "exercise_level": "Important",
             "table_layout": {
                 "table": [
@@ -484,6 +554,7 @@ const lessons = // { "courses": [...]}
                     ["|rājaputto|", "|prince|"],
                     ["|sahāyo|",    "|friend|"],
                 ],
+                "pali_cols": [0],
                 "key_col": 0,
             },
             "page": 26,
@@ -563,7 +634,7 @@ const lessons = // { "courses": [...]}
         },
         {
             "title": "Demonstrative Pronoun - this, the",
-            "categories": ["Declension"],
+            "categories": ["declension"],
             "info": "idaṃ-",
             "exercise_level": "Important",
             "table_layout": {
@@ -576,12 +647,13 @@ const lessons = // { "courses": [...]}
                     ["fem. plur.",    "|imā|",   "|imā|"],
                     ["neut. plur.",   "|imāni|", "|imāni|"],
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 30,
         },
         {
             "title": "√as Conjugation",
-            "categories": ["Conjugation"],
+            "categories": ["conjugation"],
             "info": "to be",
             "exercise_level": "Important",
             "table_layout": {
@@ -591,6 +663,7 @@ const lessons = // { "courses": [...]}
                     ["2nd", "|asi|", "|attha|"],
                     ["1st", "|asmi/amhi|", "|amha/amhā|"]
                 ],
+                "pali_cols": [1, 2],
                 "default_mode": "HoverReveal",
             },
             "page": 31,
@@ -604,12 +677,13 @@ const lessons = // { "courses": [...]}
                     ["bhavati", "|be/is|"],
                     ["atthi", "|is/becomes, eternal|"]
                 ],
+                "pali_cols": [0],
                 "default_mode": "DropDown",
             },
         },
         {
             "title": "√vac Aorist Conjugation",
-            "categories": ["Aorist"],
+            "categories": ["aorist"],
             "info": "For vacati - says ➜ said",
             "exercise_level": "Important",
             "table_layout": {
@@ -617,15 +691,16 @@ const lessons = // { "courses": [...]}
                     ["person", "singular", "plural"],
                     ["3rd", "|avoca|", "|avocuṃ|"],
                     ["2nd", "|avoca/avaca|", "|avocuttha|"],
-                    ["1st", "|avocaṃ|", "|avocumha/ā|"]
+                    ["1st", "|avocaṃ|", "|avocumha/avocumhā|"]
                 ],
+                "pali_cols": [1, 2],
                 "default_mode": "HoverReveal",
             },
             "page": 31,
         },
         {
             "title": "Cases With Vocative",
-            "categories": ["Declension"],
+            "categories": ["declension"],
             "table_layout": {
                 "table": [
                     ["case", "-a masc. sing.", "-a masc. plur."],
@@ -633,17 +708,18 @@ const lessons = // { "courses": [...]}
                     ["acc.", "-|aṃ|", "-|e|"],
                     ["voc.", "-|a|", "-|ā|"],
                 ],
+                "pali_cols": [1, 2],
                 "default_mode": "HoverReveal",
             },
             "page": 32,
         },
         {
             "title": "Vocab",
-            "categories": ["Vocab"],
+            "categories": ["vocab"],
             "exercise_level": "Important",
             "table_layout": {
                 "table": [
-                    ["|apeti|",       "|goes away/from|"],
+                    ["|apeti|",       "|goes away\\/from|"],
                     ["|tuṇhī|",       "|silent|"],
                     ["|pi|",          "|also, to|"],
                     ["|ha|",          "|indeed|"],
@@ -652,6 +728,7 @@ const lessons = // { "courses": [...]}
                     ["|paribbājako|", "|wanderer|"],
                     ["|mahārājā|",    "|great king|"],
                 ],
+                "pali_cols": [0],
                 "key_col": 0,
             },
             "page": 32,
@@ -678,7 +755,7 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "Instrumental Case Uses",
-            "categories": ["Vocab"],
+            "categories": ["vocab"],
             "table_layout": {
                 "table": [
                     ["type",             "example",                   "translation"],
@@ -712,13 +789,14 @@ const lessons = // { "courses": [...]}
                     ["|Dissociation|",   "|adaṇḍena|",                "|without force|"],
                     ["|Dissociation|",   "|aññatra brāhmaṇena|",      "|except for the priest|"],
                 ],
+                "pali_cols": [1],
                 "key_col": 1,
             },
             "page": 44,
         },
         {
             "title": "gam Present Participles",
-            "categories": ["Declension"],
+            "categories": ["declension"],
             "info": "going",
             "exercise_level": "Important",
             "table_layout": {
@@ -728,12 +806,13 @@ const lessons = // { "courses": [...]}
                     ["acc.",   "gacch|antaṃ|",   "gacch|ante|"],
                     ["instr.", "gacch|atā|",     "gacch|antehi|"],
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 46,
         },
         {
             "title": "bhavant Pronoun",
-            "categories": ["Declension"],
+            "categories": ["declension"],
             "info": "you, sir, your honor",
             "table_layout": {
                 "table": [
@@ -743,6 +822,7 @@ const lessons = // { "courses": [...]}
                     ["instr.", "|bhotā|",     "|bhavantehi|"],
                     ["voc.",   "|bho|",       "|bhonto|"],
                 ],
+                "pali_cols": [1, 2],
                 "default_mode": "HoverReveal",
             },
             "page": 47,
@@ -756,16 +836,17 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "√(p)pa-hā Present Indicative Passive Conjugation",
-            "categories": ["Tam"],
+            "categories": ["tam"],
             "info": "pahita, give up --> it is given up",
             "exercise_level": "Important",
             "table_layout": {
                 "table": [
                     ["person", "singular", "plural"],
                     ["3rd", "pah|īyati|", "pah|īyanti|"],
-                    ["2nd", "pah|īyasi|", "pah|īyata|"],
-                    ["1st", "pah|īyāmi|", "pah|īyama|"]
+                    ["2nd", "pah|īyasi|", "pah|īyatha|"],
+                    ["1st", "pah|īyāmi|", "pah|īyāma|"]
                 ],
+                "pali_cols": [1, 2],
                 "key_col": 0,
             },
             "explanation": "passive transformation: add ya or tya to the root, which often loses its last vowel.",
@@ -773,7 +854,7 @@ const lessons = // { "courses": [...]}
         },
         {
             "title": "-ā Feminine Noun Ending",
-            "categories": ["Declension"],
+            "categories": ["declension"],
             "info": "For kathā",
             "exercise_level": "Important",
             "table_layout": {
@@ -783,6 +864,7 @@ const lessons = // { "courses": [...]}
                     ["acc.",   "kath|aṃ|",  "kath|āyo|"],
                     ["instr.", "kath|āya|", "kath|āhi|"],
                 ],
+                "pali_cols": [1, 2],
             },
             "page": 46,
         },
@@ -801,7 +883,7 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "√man Third Conjugation",
-            "categories": ["Conjugation"],
+            "categories": ["conjugation"],
             "info": "pahita, give up ➜ it is given up",
             "exercise_level": "Important",
             "table_layout": {
@@ -811,6 +893,7 @@ const lessons = // { "courses": [...]}
                     ["2nd", "ma|ññasi|", "ma|ññatha|"],
                     ["1st", "ma|ññāmi|", "ma|ññāma|"]
                 ],
+                "pali_cols": [1, 2],
             },
             "explanation": "forms like passive with first conjugation endings: (man + ya = mañña).",
             "page": 51,
@@ -830,7 +913,7 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "√bhū Present Causative Conjugation",
-            "categories": ["Tam"],
+            "categories": ["tam"],
             "info": "causes to be",
             "exercise_level": "Important",
             "table_layout": {
@@ -840,10 +923,16 @@ const lessons = // { "courses": [...]}
                     ["2nd", "|bhāvesi|", "|bhāvetha|"],
                     ["1st", "|bhāvemi|", "|bhāvema|"]
                 ],
+                "pali_cols": [1, 2],
             },
             "explanation": "stems form like seventh conjugation.",
             "page": 80,
         },
+        {
+            "title": "Sandhi",
+            "info": "Combine the two. Any way the sandhi rules allow is right.",
+            "paradigm": { "type": "Sandhi", "pairs": [["na", "atthi"], ["ca", "eva"], ["taṃ", "ahaṃ"], ["su", "āgataṃ"], ["saṃ", "buddha"], ["atthi", "iti"]] },
+        },
     ]
 },
 {
@@ -852,17 +941,10 @@ const lessons = // { "courses": [...]}
     "exercises": [
         {
             "title": "√bhū Optative Conjugation",
-            "categories": ["Tam"],
+            "categories": ["tam"],
             "info": "should/could/may be",
             "exercise_level": "Important",
-            "table_layout": {
-                "table": [
-                    ["person", "singular",  "plural"],
-                    ["3rd", "bhav|eyya|",   "bhav|eyyuṃ|"],
-                    ["2nd", "bhav|eyyāsi|", "bhav|eyyātha|"],
-                    ["1st", "bhav|eyyaṃ|",  "bhav|eyyāma|"]
-                ],
-            },
+            "paradigm": { "type": "Conjugation", "stem": "bhav-", "class": 1, "tense": "Optative" },
             "page": 86,
         },
         {
@@ -916,3 +998,4 @@ Lesson 18 - i Declension
 
 
 ]}
+]}